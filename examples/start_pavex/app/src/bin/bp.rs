@@ -1,8 +1,12 @@
 use app::blueprint;
+use app::routes::leptos_routes;
 use cargo_px_env::generated_pkg_manifest_path;
+use leptos_pavex::manifest::RouteManifest;
+use leptos_pavex::mount::MountPoint;
 use pavex_cli_client::Client;
 use std::env::args;
 use std::error::Error;
+use std::path::PathBuf;
 
 /// Generate the `server_sdk` crate using Pavex's CLI.
 ///
@@ -10,17 +14,33 @@ use std::error::Error;
 /// into a "server SDK" that can be used by the final API server binary to launch
 /// the application.
 ///
+/// It also writes a manifest of the Leptos routes and server functions,
+/// `leptos_routes.json`, next to the generated crate.
+///
 /// If `--check` is passed as an argument, it only verifies that the server SDK
-/// crate is up-to-date. An error is returned if it isn't.
+/// crate and the route manifest are up-to-date. An error is returned if they aren't.
 fn main() -> Result<(), Box<dyn Error>> {
-    let generated_dir = generated_pkg_manifest_path()?.parent().unwrap().into();
-    let mut cmd = Client::new().generate(blueprint(), generated_dir);
-    if args().any(|arg| arg == "--check") {
+    let generated_dir: PathBuf = generated_pkg_manifest_path()?.parent().unwrap().into();
+    let check = args().any(|arg| arg == "--check");
+    let mut cmd = Client::new().generate(blueprint(), generated_dir.clone());
+    if check {
         cmd = cmd.check()
     };
     if let Err(e) = cmd.execute() {
         eprintln!("{e}");
         std::process::exit(1);
     }
+
+    let manifest = RouteManifest::new(&leptos_routes(), &MountPoint::root());
+    let manifest_path = generated_dir.join("leptos_routes.json");
+    if !check {
+        manifest.write(&manifest_path)?;
+    } else if !manifest.is_written_at(&manifest_path) {
+        eprintln!(
+            "The route manifest at {} is out of date",
+            manifest_path.display()
+        );
+        std::process::exit(1);
+    }
     Ok(())
 }
@@ -1,46 +1,69 @@
+use app_errors::EcommerceAppError;
 use leptos::prelude::{
-    provide_context, view, ElementChild, GlobalAttributes, HydrationScripts, IntoAny, LeptosOptions,
+    provide_context, view, ElementChild, GlobalAttributes, HydrationScripts, IntoAny, IntoView,
+    LeptosOptions,
 };
 use leptos_app::pages::App;
 use leptos_meta::MetaTags;
+use leptos_pavex::error_status::ErrorStatuses;
+use leptos_pavex::origin::RequestOrigin;
 use leptos_pavex::pavex_helpers::{AppFunction, ComponentOwner, ServerFnOwner};
-use leptos_pavex::{pass_leptos_context, RouteType};
+use leptos_pavex::{pass_leptos_context_with_origin, RouteType};
 use pavex::request::RequestHead;
 
+/// The HTML shell of the app, rendered for every Leptos route.
+pub fn shell(options: LeptosOptions) -> impl IntoView {
+    view! {
+        <!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <meta charset="utf-8"/>
+                <meta name="viewport" content="width=device-width, initial-scale=1"/>
+                // <AutoReload options=options.clone() />
+                <HydrationScripts options/>
+                <MetaTags/>
+            </head>
+            <body>
+                <App/>
+            </body>
+        </html>
+    }
+}
+
 pub fn generate_app(context: &ComponentOwner, options: LeptosOptions) -> AppFunction {
     let owner = context.owner();
-    let fun = move || {
-        view! {
-            <!DOCTYPE html>
-            <html lang="en">
-                <head>
-                    <meta charset="utf-8"/>
-                    <meta name="viewport" content="width=device-width, initial-scale=1"/>
-                    // <AutoReload options=options.clone() />
-                    <HydrationScripts options/>
-                    <MetaTags/>
-                </head>
-                <body>
-                    <App/>
-                </body>
-            </html>
-        }
-    };
-    AppFunction::new(owner.with(fun).into_any())
+    AppFunction::new(owner.with(move || shell(options)).into_any())
+}
+
+/// Context provided to the app for every request, and when rendering static routes.
+pub fn additional_context() {
+    // Pass additional context items here
+    provide_context("Test".to_string());
 }
 
-pub fn additional_context_components(req_head: &RequestHead) -> ComponentOwner {
-    let (owner, meta_context_output) = pass_leptos_context(&RouteType::Component, req_head, || {
-        // Pass additional context items here
-        provide_context("Test".to_string());
-    });
+pub fn additional_context_components(
+    req_head: &RequestHead,
+    origin: &RequestOrigin,
+) -> ComponentOwner {
+    let (owner, meta_context_output) = pass_leptos_context_with_origin(
+        &RouteType::Component,
+        req_head,
+        origin,
+        additional_context,
+    );
     ComponentOwner::new(owner, meta_context_output)
 }
 
-pub fn additional_context_serverfn(req_head: &RequestHead) -> ServerFnOwner {
-    let (owner, _) = pass_leptos_context(&RouteType::ServerFn, req_head, || {
-        // Pass additional context items here
-        provide_context("Test".to_string());
-    });
+pub fn additional_context_serverfn(
+    req_head: &RequestHead,
+    origin: &RequestOrigin,
+) -> ServerFnOwner {
+    let (owner, _) =
+        pass_leptos_context_with_origin(&RouteType::ServerFn, req_head, origin, additional_context);
     ServerFnOwner::new(owner)
 }
+
+/// Send the app's errors from server functions with their own status codes.
+pub fn error_statuses() -> ErrorStatuses {
+    ErrorStatuses::new().with_status(EcommerceAppError::status_code)
+}
@@ -1,31 +1,48 @@
 pub mod greet;
 pub mod ping;
 
-use http::Request;
+use http::Method;
 use leptos::config::get_configuration;
-use leptos_pavex::leptos_routes::add_leptos_routes;
+use leptos_pavex::kit::LeptosKit;
+use leptos_pavex::route_conflicts::ReservedRoutes;
+use leptos_pavex::PavexRouteList;
+use pavex::blueprint::constructor::Constructor;
 use pavex::blueprint::{router::GET, Blueprint};
 use pavex::f;
-use pavex::request::RequestHead;
 
-use crate::leptos::generate_app;
+use crate::leptos::{additional_context, shell};
 
-pub fn register(bp: &mut Blueprint) {
+/// The routes defined in Leptos, generated from the app shell.
+pub fn leptos_routes() -> PavexRouteList {
     let conf = get_configuration(None).unwrap();
     let leptos_options = conf.leptos_options;
+    leptos_pavex::discover_routes_with_context(
+        move || shell(leptos_options.clone()),
+        additional_context,
+    )
+}
 
-    // Generate routes for routes defined in Leptos for components and server fns
-    let mock_request = Request::builder()
-        .uri("https://www.leptos.dev/about")
-        .body(())
-        .unwrap();
-    let mock_req_head: RequestHead = mock_request.into_parts().0.into();
-    let routes = leptos_pavex::generate_route_list(generate_app(
-        &crate::leptos::additional_context_components(&mock_req_head),
-        leptos_options,
-    ));
-    add_leptos_routes(&routes, bp);
+pub fn register(bp: &mut Blueprint) {
+    // Register the routes defined in Leptos for components and server fns and everything they
+    // need, checking that they don't conflict with the routes below. Other requests are served from
+    // the static files, or get the app's not found page
+    let reserved = ReservedRoutes::new()
+        .with_route(Method::GET, "/api/ping")
+        .with_route(Method::GET, "/api/greet/:name");
+    LeptosKit::new(
+        leptos_routes(),
+        Constructor::request_scoped(f!(crate::leptos::generate_app)),
+    )
+    .with_reserved_routes(reserved)
+    .with_component_owner(Constructor::request_scoped(f!(
+        crate::leptos::additional_context_components
+    )))
+    .with_server_fn_owner(Constructor::request_scoped(f!(
+        crate::leptos::additional_context_serverfn
+    )))
+    .with_error_statuses(Constructor::singleton(f!(crate::leptos::error_statuses)))
+    .with_fallback()
+    .register(bp);
     bp.route(GET, "/api/ping", f!(self::ping::get));
     bp.route(GET, "/api/greet/:name", f!(self::greet::get));
-    bp.route(GET, "/*path", f!(leptos_pavex::file_helpers::serve_files));
 }
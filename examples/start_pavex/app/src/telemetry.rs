@@ -51,6 +51,8 @@ pub fn root_span(
         { ERROR_MESSAGE } = tracing::field::Empty,
         { ERROR_DETAILS } = tracing::field::Empty,
         { ERROR_SOURCE_CHAIN } = tracing::field::Empty,
+        // Set by leptos_pavex when a route misses its `RenderDeadline`.
+        timed_out = tracing::field::Empty,
     );
     RootSpan::new(span)
 }
@@ -30,13 +30,10 @@ cfg_if! {
                         
                         embed_migrations!("./../server/migrations");
 
-                        let conn = Arc::new(Mutex::new(
+                        Arc::new(Mutex::new(
                             Connection::open("./../db.db3")
                                 .expect("Failed to connect to DB")
-    )
-                        );
-
-                        conn
+                        ))
                     }));
                     if let Err(e) = PTR.compare_exchange(null_mut(), p, Release, Acquire) {
                         // Safety: p comes from Box::into_raw right above,
@@ -46,7 +43,7 @@ cfg_if! {
                         p = e;
                     }
                 }
-                Ok(Arc::clone(&*(unsafe { &*p })))
+                Ok(Arc::clone(unsafe { &*p }))
 
             } else {
                 let raw_conn = match use_context::<Arc<Mutex<Connection>>>(){
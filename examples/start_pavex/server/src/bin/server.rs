@@ -1,9 +1,8 @@
 use anyhow::Context;
-use app::leptos::{additional_context_components, generate_app};
-use http::Request;
+use app::leptos::{additional_context, shell};
 use leptos::prelude::get_configuration;
-use leptos_pavex::generate_route_list;
-use pavex::request::RequestHead;
+use leptos_pavex::discover_routes_with_context;
+use leptos_pavex::static_routes::{build_static_routes, StaticParams};
 use pavex::server::{Server, ServerHandle, ShutdownMode};
 use pavex_tracing::fields::{error_details, error_message, ERROR_DETAILS, ERROR_MESSAGE};
 use server::{
@@ -44,15 +43,23 @@ async fn _main() -> anyhow::Result<()> {
     let leptos_options = conf.leptos_options;
 
     // Generate Leptos Route list
-    let mock_request = Request::builder()
-        .uri("https://www.leptos.dev/about")
-        .body(())
-        .unwrap();
-    let mock_req_head: RequestHead = mock_request.into_parts().0.into();
-    let routes = generate_route_list(generate_app(
-        &additional_context_components(&mock_req_head),
-        leptos_options.clone(),
-    ));
+    let app_fn = {
+        let leptos_options = leptos_options.clone();
+        move || shell(leptos_options.clone())
+    };
+    let routes = discover_routes_with_context(app_fn.clone(), additional_context);
+
+    // Render the routes marked as statically generated up front into the site root
+    let static_params = StaticParams::new();
+    build_static_routes(
+        &leptos_options,
+        app_fn,
+        additional_context,
+        &routes,
+        &static_params,
+    )
+    .await
+    .context("Failed to build the static routes")?;
 
     let application_state = build_application_state(routes, leptos_options, config.app).await;
     let tcp_listener = config
@@ -1,3 +1,6 @@
+use app::leptos::{additional_context, shell};
+use leptos::prelude::get_configuration;
+use leptos_pavex::discover_routes_with_context;
 use pavex::server::Server;
 use server::configuration::{ApplicationProfile, Config};
 use server_sdk::{build_application_state, run};
@@ -15,7 +18,16 @@ impl TestApi {
         Self::init_telemetry();
         let config = Self::get_config();
 
-        let application_state = build_application_state().await;
+        let leptos_options = get_configuration(None).unwrap().leptos_options;
+        let routes = discover_routes_with_context(
+            {
+                let leptos_options = leptos_options.clone();
+                move || shell(leptos_options.clone())
+            },
+            additional_context,
+        );
+        let application_state =
+            build_application_state(routes, leptos_options, config.app.clone()).await;
 
         let tcp_listener = config
             .server
@@ -70,7 +82,7 @@ impl TestApi {
 impl TestApi {
     pub async fn get_ping(&self) -> reqwest::Response {
         self.api_client
-            .get(&format!("{}/api/ping", &self.api_address))
+            .get(format!("{}/api/ping", &self.api_address))
             .send()
             .await
             .expect("Failed to execute request.")
@@ -7,19 +7,39 @@ struct ServerState {
     application_state: ApplicationState,
 }
 pub struct ApplicationState {
-    s0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-    s1: leptos_config::LeptosOptions,
-    s2: app::configuration::AppConfig,
+    s0: leptos_config::LeptosOptions,
+    s1: leptos_pavex::render_config::RenderConfig,
+    s2: leptos_pavex::route_table::PavexRouteTable,
+    s3: leptos_pavex::origin::TrustedProxies,
+    s4: leptos_pavex::mount::MountPoint,
+    s5: leptos_pavex::cors::CorsPolicy,
+    s6: leptos_pavex::body_limit::ServerFnBodyLimits,
+    s7: leptos_pavex::error_status::ErrorStatuses,
+    s8: app::configuration::AppConfig,
 }
 pub async fn build_application_state(
     v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
     v1: leptos_config::LeptosOptions,
     v2: app::configuration::AppConfig,
 ) -> crate::ApplicationState {
+    let v3 = leptos_pavex::route_table::PavexRouteTable::new(&v0);
+    let v4 = leptos_pavex::render_options::RenderOptions::new();
+    let v5 = leptos_pavex::render_config::RenderConfig::new(&v3, &v1, &v4);
+    let v6 = leptos_pavex::origin::TrustedProxies::new();
+    let v7 = leptos_pavex::mount::MountPoint::root();
+    let v8 = leptos_pavex::cors::CorsPolicy::new();
+    let v9 = leptos_pavex::body_limit::ServerFnBodyLimits::new();
+    let v10 = app::leptos::error_statuses();
     crate::ApplicationState {
-        s0: v0,
-        s1: v1,
-        s2: v2,
+        s0: v1,
+        s1: v5,
+        s2: v3,
+        s3: v6,
+        s4: v7,
+        s5: v8,
+        s6: v9,
+        s7: v10,
+        s8: v2,
     }
 }
 pub fn run(
@@ -35,22 +55,22 @@ pub fn run(
 fn build_router() -> pavex_matchit::Router<u32> {
     let mut router = pavex_matchit::Router::new();
     router.insert("/", 0u32).unwrap();
-    router.insert("/*path", 1u32).unwrap();
-    router.insert("/about", 2u32).unwrap();
-    router.insert("/api/greet", 3u32).unwrap();
-    router.insert("/api/greet/:name", 4u32).unwrap();
-    router.insert("/api/ping", 5u32).unwrap();
+    router.insert("/about", 1u32).unwrap();
+    router.insert("/api/greet", 2u32).unwrap();
+    router.insert("/api/greet/:name", 3u32).unwrap();
+    router.insert("/api/ping", 4u32).unwrap();
     router
 }
 async fn route_request(
     request: http::Request<hyper::body::Incoming>,
-    _connection_info: Option<pavex::connection::ConnectionInfo>,
+    connection_info: Option<pavex::connection::ConnectionInfo>,
     server_state: std::sync::Arc<ServerState>,
 ) -> pavex::response::Response {
     let (request_head, request_body) = request.into_parts();
     #[allow(unused)]
     let request_body = pavex::request::body::RawIncomingBody::from(request_body);
     let request_head: pavex::request::RequestHead = request_head.into();
+    let connection_info = connection_info.expect("Required ConnectionInfo is missing");
     let matched_route = match server_state.router.at(&request_head.target.path()) {
         Ok(m) => m,
         Err(_) => {
@@ -61,10 +81,16 @@ async fn route_request(
             let matched_route_template = pavex::request::path::MatchedPathPattern::new(
                 "*",
             );
-            return route_6::entrypoint(
-                    &request_head,
+            return route_10::entrypoint(
+                    request_head,
+                    request_body,
                     matched_route_template,
                     &allowed_methods,
+                    &connection_info,
+                    &server_state.application_state.s3,
+                    &server_state.application_state.s4,
+                    server_state.application_state.s0.clone(),
+                    &server_state.application_state.s1,
                 )
                 .await;
         }
@@ -81,86 +107,123 @@ async fn route_request(
             );
             match &request_head.method {
                 &pavex::http::Method::GET => {
-                    route_1::entrypoint(
-                            server_state.application_state.s0.clone(),
+                    route_2::entrypoint(
                             request_head,
                             request_body,
-                            server_state.application_state.s1.clone(),
                             matched_route_template,
+                            &connection_info,
+                            &server_state.application_state.s3,
+                            &server_state.application_state.s4,
+                            server_state.application_state.s0.clone(),
+                            &server_state.application_state.s1,
                         )
                         .await
                 }
-                _ => {
-                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
-                            pavex::http::Method::GET,
-                        ])
-                        .into();
-                    route_6::entrypoint(
-                            &request_head,
+                &pavex::http::Method::HEAD => {
+                    route_3::entrypoint(
+                            request_head,
+                            request_body,
                             matched_route_template,
-                            &allowed_methods,
+                            &connection_info,
+                            &server_state.application_state.s3,
+                            &server_state.application_state.s4,
+                            server_state.application_state.s0.clone(),
+                            &server_state.application_state.s1,
                         )
                         .await
                 }
-            }
-        }
-        1u32 => {
-            let matched_route_template = pavex::request::path::MatchedPathPattern::new(
-                "/*path",
-            );
-            match &request_head.method {
-                &pavex::http::Method::GET => {
-                    route_5::entrypoint(
-                            url_params,
-                            matched_route_template,
+                &pavex::http::Method::OPTIONS => {
+                    route_4::entrypoint(
                             &request_head,
+                            matched_route_template,
+                            &server_state.application_state.s2,
                         )
                         .await
                 }
                 _ => {
                     let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
                             pavex::http::Method::GET,
+                            pavex::http::Method::HEAD,
+                            pavex::http::Method::OPTIONS,
                         ])
                         .into();
-                    route_6::entrypoint(
-                            &request_head,
+                    route_10::entrypoint(
+                            request_head,
+                            request_body,
                             matched_route_template,
                             &allowed_methods,
+                            &connection_info,
+                            &server_state.application_state.s3,
+                            &server_state.application_state.s4,
+                            server_state.application_state.s0.clone(),
+                            &server_state.application_state.s1,
                         )
                         .await
                 }
             }
         }
-        2u32 => {
+        1u32 => {
             let matched_route_template = pavex::request::path::MatchedPathPattern::new(
                 "/about",
             );
             match &request_head.method {
                 &pavex::http::Method::GET => {
-                    route_2::entrypoint(
+                    route_5::entrypoint(
+                            request_head,
+                            request_body,
+                            matched_route_template,
+                            &connection_info,
+                            &server_state.application_state.s3,
+                            &server_state.application_state.s4,
                             server_state.application_state.s0.clone(),
+                            &server_state.application_state.s1,
+                        )
+                        .await
+                }
+                &pavex::http::Method::HEAD => {
+                    route_6::entrypoint(
                             request_head,
                             request_body,
-                            server_state.application_state.s1.clone(),
                             matched_route_template,
+                            &connection_info,
+                            &server_state.application_state.s3,
+                            &server_state.application_state.s4,
+                            server_state.application_state.s0.clone(),
+                            &server_state.application_state.s1,
+                        )
+                        .await
+                }
+                &pavex::http::Method::OPTIONS => {
+                    route_7::entrypoint(
+                            &request_head,
+                            matched_route_template,
+                            &server_state.application_state.s2,
                         )
                         .await
                 }
                 _ => {
                     let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
                             pavex::http::Method::GET,
+                            pavex::http::Method::HEAD,
+                            pavex::http::Method::OPTIONS,
                         ])
                         .into();
-                    route_6::entrypoint(
-                            &request_head,
+                    route_10::entrypoint(
+                            request_head,
+                            request_body,
                             matched_route_template,
                             &allowed_methods,
+                            &connection_info,
+                            &server_state.application_state.s3,
+                            &server_state.application_state.s4,
+                            server_state.application_state.s0.clone(),
+                            &server_state.application_state.s1,
                         )
                         .await
                 }
             }
         }
-        3u32 => {
+        2u32 => {
             let matched_route_template = pavex::request::path::MatchedPathPattern::new(
                 "/api/greet",
             );
@@ -170,34 +233,55 @@ async fn route_request(
                             request_head,
                             request_body,
                             matched_route_template,
+                            &connection_info,
+                            &server_state.application_state.s3,
+                            &server_state.application_state.s4,
+                            &server_state.application_state.s5,
+                            &server_state.application_state.s6,
+                            &server_state.application_state.s7,
+                        )
+                        .await
+                }
+                &pavex::http::Method::OPTIONS => {
+                    route_1::entrypoint(
+                            &request_head,
+                            matched_route_template,
+                            &server_state.application_state.s5,
                         )
                         .await
                 }
                 _ => {
                     let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
                             pavex::http::Method::POST,
+                            pavex::http::Method::OPTIONS,
                         ])
                         .into();
-                    route_6::entrypoint(
-                            &request_head,
+                    route_10::entrypoint(
+                            request_head,
+                            request_body,
                             matched_route_template,
                             &allowed_methods,
+                            &connection_info,
+                            &server_state.application_state.s3,
+                            &server_state.application_state.s4,
+                            server_state.application_state.s0.clone(),
+                            &server_state.application_state.s1,
                         )
                         .await
                 }
             }
         }
-        4u32 => {
+        3u32 => {
             let matched_route_template = pavex::request::path::MatchedPathPattern::new(
                 "/api/greet/:name",
             );
             match &request_head.method {
                 &pavex::http::Method::GET => {
-                    route_4::entrypoint(
+                    route_9::entrypoint(
                             url_params,
                             &request_head,
                             matched_route_template,
-                            &server_state.application_state.s2,
+                            &server_state.application_state.s8,
                         )
                         .await
                 }
@@ -206,32 +290,44 @@ async fn route_request(
                             pavex::http::Method::GET,
                         ])
                         .into();
-                    route_6::entrypoint(
-                            &request_head,
+                    route_10::entrypoint(
+                            request_head,
+                            request_body,
                             matched_route_template,
                             &allowed_methods,
+                            &connection_info,
+                            &server_state.application_state.s3,
+                            &server_state.application_state.s4,
+                            server_state.application_state.s0.clone(),
+                            &server_state.application_state.s1,
                         )
                         .await
                 }
             }
         }
-        5u32 => {
+        4u32 => {
             let matched_route_template = pavex::request::path::MatchedPathPattern::new(
                 "/api/ping",
             );
             match &request_head.method {
                 &pavex::http::Method::GET => {
-                    route_3::entrypoint(matched_route_template, &request_head).await
+                    route_8::entrypoint(matched_route_template, &request_head).await
                 }
                 _ => {
                     let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
                             pavex::http::Method::GET,
                         ])
                         .into();
-                    route_6::entrypoint(
-                            &request_head,
+                    route_10::entrypoint(
+                            request_head,
+                            request_body,
                             matched_route_template,
                             &allowed_methods,
+                            &connection_info,
+                            &server_state.application_state.s3,
+                            &server_state.application_state.s4,
+                            server_state.application_state.s0.clone(),
+                            &server_state.application_state.s1,
                         )
                         .await
                 }
@@ -241,71 +337,130 @@ async fn route_request(
     }
 }
 pub mod route_0 {
-    pub async fn entrypoint(
+    pub async fn entrypoint<'a, 'b, 'c, 'd, 'e, 'f>(
         s_0: pavex::request::RequestHead,
         s_1: pavex::request::body::RawIncomingBody,
         s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: &'d leptos_pavex::cors::CorsPolicy,
+        s_7: &'e leptos_pavex::body_limit::ServerFnBodyLimits,
+        s_8: &'f leptos_pavex::error_status::ErrorStatuses,
     ) -> pavex::response::Response {
-        let response = wrapping_0(s_0, s_1, s_2).await;
+        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7, s_8).await;
         response
     }
-    async fn stage_1(
+    async fn stage_1<'a, 'b, 'c, 'd, 'e, 'f>(
         s_0: pavex::request::RequestHead,
         s_1: pavex::request::body::RawIncomingBody,
         s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: &'d leptos_pavex::cors::CorsPolicy,
+        s_7: &'e leptos_pavex::body_limit::ServerFnBodyLimits,
+        s_8: &'f leptos_pavex::error_status::ErrorStatuses,
     ) -> pavex::response::Response {
-        let response = wrapping_1(s_0, s_1, s_2).await;
+        let response = wrapping_1(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7, s_8).await;
         response
     }
-    async fn stage_2<'a>(
+    async fn stage_2<'a, 'b, 'c, 'd, 'e, 'f, 'g>(
         s_0: pavex::request::RequestHead,
         s_1: pavex::request::body::RawIncomingBody,
-        s_2: &'a pavex_tracing::RootSpan,
+        s_2: &'a pavex::connection::ConnectionInfo,
+        s_3: &'b leptos_pavex::origin::TrustedProxies,
+        s_4: &'c leptos_pavex::mount::MountPoint,
+        s_5: &'d leptos_pavex::cors::CorsPolicy,
+        s_6: &'e leptos_pavex::body_limit::ServerFnBodyLimits,
+        s_7: &'f leptos_pavex::error_status::ErrorStatuses,
+        s_8: &'g pavex_tracing::RootSpan,
     ) -> pavex::response::Response {
-        let response = handler(s_0, s_1).await;
-        let response = post_processing_0(response, s_2).await;
+        let response = handler(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
+        let response = post_processing_0(response, s_8).await;
         response
     }
     async fn wrapping_0(
         v0: pavex::request::RequestHead,
         v1: pavex::request::body::RawIncomingBody,
         v2: pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: &leptos_pavex::cors::CorsPolicy,
+        v7: &leptos_pavex::body_limit::ServerFnBodyLimits,
+        v8: &leptos_pavex::error_status::ErrorStatuses,
     ) -> pavex::response::Response {
-        let v3 = crate::route_0::Next0 {
+        let v9 = crate::route_0::Next0 {
             s_0: v0,
             s_1: v1,
             s_2: v2,
+            s_3: v3,
+            s_4: v4,
+            s_5: v5,
+            s_6: v6,
+            s_7: v7,
+            s_8: v8,
             next: stage_1,
         };
-        let v4 = pavex::middleware::Next::new(v3);
-        let v5 = pavex::middleware::wrap_noop(v4).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v5)
+        let v10 = pavex::middleware::Next::new(v9);
+        let v11 = pavex::middleware::wrap_noop(v10).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v11)
     }
     async fn wrapping_1(
         v0: pavex::request::RequestHead,
         v1: pavex::request::body::RawIncomingBody,
         v2: pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: &leptos_pavex::cors::CorsPolicy,
+        v7: &leptos_pavex::body_limit::ServerFnBodyLimits,
+        v8: &leptos_pavex::error_status::ErrorStatuses,
     ) -> pavex::response::Response {
-        let v3 = pavex::telemetry::ServerRequestId::generate();
-        let v4 = app::telemetry::root_span(&v0, v2, v3);
-        let v5 = crate::route_0::Next1 {
+        let v9 = pavex::telemetry::ServerRequestId::generate();
+        let v10 = app::telemetry::root_span(&v0, v2, v9);
+        let v11 = crate::route_0::Next1 {
             s_0: v0,
             s_1: v1,
-            s_2: &v4,
+            s_2: v3,
+            s_3: v4,
+            s_4: v5,
+            s_5: v6,
+            s_6: v7,
+            s_7: v8,
+            s_8: &v10,
             next: stage_2,
         };
-        let v6 = pavex::middleware::Next::new(v5);
-        let v7 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v4);
-        let v8 = pavex_tracing::logger(v7, v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v8)
+        let v12 = pavex::middleware::Next::new(v11);
+        let v13 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v10);
+        let v14 = pavex_tracing::logger(v13, v12).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v14)
     }
     async fn handler(
         v0: pavex::request::RequestHead,
         v1: pavex::request::body::RawIncomingBody,
+        v2: &pavex::connection::ConnectionInfo,
+        v3: &leptos_pavex::origin::TrustedProxies,
+        v4: &leptos_pavex::mount::MountPoint,
+        v5: &leptos_pavex::cors::CorsPolicy,
+        v6: &leptos_pavex::body_limit::ServerFnBodyLimits,
+        v7: &leptos_pavex::error_status::ErrorStatuses,
     ) -> pavex::response::Response {
-        let v2 = app::leptos::additional_context_serverfn(&v0);
-        let v3 = leptos_pavex::server_fn::handle_server_fns(v0, v1, v2).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v3)
+        let v8 = leptos_pavex::origin::RequestOrigin::extract(&v0, v2, v3, v4);
+        let v9 = app::leptos::additional_context_serverfn(&v0, &v8);
+        let v10 = <pavex::request::body::BodySizeLimit as std::default::Default>::default();
+        let v11 = leptos_pavex::server_fn::handle_server_fns(
+                v0,
+                v1,
+                v9,
+                v5,
+                &v10,
+                v6,
+                v7,
+            )
+            .await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v11)
     }
     async fn post_processing_0(
         v0: pavex::response::Response,
@@ -314,139 +469,1082 @@ pub mod route_0 {
         let v2 = app::telemetry::response_logger(v0, v1).await;
         <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
     }
-    struct Next0<T>
+    struct Next0<'a, 'b, 'c, 'd, 'e, 'f, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         s_0: pavex::request::RequestHead,
         s_1: pavex::request::body::RawIncomingBody,
         s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: &'d leptos_pavex::cors::CorsPolicy,
+        s_7: &'e leptos_pavex::body_limit::ServerFnBodyLimits,
+        s_8: &'f leptos_pavex::error_status::ErrorStatuses,
         next: fn(
             pavex::request::RequestHead,
             pavex::request::body::RawIncomingBody,
             pavex::request::path::MatchedPathPattern,
+            &'a pavex::connection::ConnectionInfo,
+            &'b leptos_pavex::origin::TrustedProxies,
+            &'c leptos_pavex::mount::MountPoint,
+            &'d leptos_pavex::cors::CorsPolicy,
+            &'e leptos_pavex::body_limit::ServerFnBodyLimits,
+            &'f leptos_pavex::error_status::ErrorStatuses,
         ) -> T,
     }
-    impl<T> std::future::IntoFuture for Next0<T>
+    impl<'a, 'b, 'c, 'd, 'e, 'f, T> std::future::IntoFuture
+    for Next0<'a, 'b, 'c, 'd, 'e, 'f, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2)
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+                self.s_7,
+                self.s_8,
+            )
         }
     }
-    struct Next1<'a, T>
+    struct Next1<'a, 'b, 'c, 'd, 'e, 'f, 'g, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         s_0: pavex::request::RequestHead,
         s_1: pavex::request::body::RawIncomingBody,
-        s_2: &'a pavex_tracing::RootSpan,
+        s_2: &'a pavex::connection::ConnectionInfo,
+        s_3: &'b leptos_pavex::origin::TrustedProxies,
+        s_4: &'c leptos_pavex::mount::MountPoint,
+        s_5: &'d leptos_pavex::cors::CorsPolicy,
+        s_6: &'e leptos_pavex::body_limit::ServerFnBodyLimits,
+        s_7: &'f leptos_pavex::error_status::ErrorStatuses,
+        s_8: &'g pavex_tracing::RootSpan,
         next: fn(
             pavex::request::RequestHead,
             pavex::request::body::RawIncomingBody,
-            &'a pavex_tracing::RootSpan,
+            &'a pavex::connection::ConnectionInfo,
+            &'b leptos_pavex::origin::TrustedProxies,
+            &'c leptos_pavex::mount::MountPoint,
+            &'d leptos_pavex::cors::CorsPolicy,
+            &'e leptos_pavex::body_limit::ServerFnBodyLimits,
+            &'f leptos_pavex::error_status::ErrorStatuses,
+            &'g pavex_tracing::RootSpan,
         ) -> T,
     }
-    impl<'a, T> std::future::IntoFuture for Next1<'a, T>
+    impl<'a, 'b, 'c, 'd, 'e, 'f, 'g, T> std::future::IntoFuture
+    for Next1<'a, 'b, 'c, 'd, 'e, 'f, 'g, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2)
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+                self.s_7,
+                self.s_8,
+            )
         }
     }
 }
 pub mod route_1 {
-    pub async fn entrypoint(
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: pavex::request::path::MatchedPathPattern,
-    ) -> pavex::response::Response {
-        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4).await;
+    pub async fn entrypoint<'a, 'b>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: pavex::request::path::MatchedPathPattern,
+        s_2: &'b leptos_pavex::cors::CorsPolicy,
+    ) -> pavex::response::Response {
+        let response = wrapping_0(s_0, s_1, s_2).await;
+        response
+    }
+    async fn stage_1<'a, 'b>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: pavex::request::path::MatchedPathPattern,
+        s_2: &'b leptos_pavex::cors::CorsPolicy,
+    ) -> pavex::response::Response {
+        let response = wrapping_1(s_0, s_1, s_2).await;
+        response
+    }
+    async fn stage_2<'a, 'b, 'c>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: &'b leptos_pavex::cors::CorsPolicy,
+        s_2: &'c pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let response = handler(s_0, s_1).await;
+        let response = post_processing_0(response, s_2).await;
+        response
+    }
+    async fn wrapping_0(
+        v0: &pavex::request::RequestHead,
+        v1: pavex::request::path::MatchedPathPattern,
+        v2: &leptos_pavex::cors::CorsPolicy,
+    ) -> pavex::response::Response {
+        let v3 = crate::route_1::Next0 {
+            s_0: v0,
+            s_1: v1,
+            s_2: v2,
+            next: stage_1,
+        };
+        let v4 = pavex::middleware::Next::new(v3);
+        let v5 = pavex::middleware::wrap_noop(v4).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v5)
+    }
+    async fn wrapping_1(
+        v0: &pavex::request::RequestHead,
+        v1: pavex::request::path::MatchedPathPattern,
+        v2: &leptos_pavex::cors::CorsPolicy,
+    ) -> pavex::response::Response {
+        let v3 = pavex::telemetry::ServerRequestId::generate();
+        let v4 = app::telemetry::root_span(v0, v1, v3);
+        let v5 = crate::route_1::Next1 {
+            s_0: v0,
+            s_1: v2,
+            s_2: &v4,
+            next: stage_2,
+        };
+        let v6 = pavex::middleware::Next::new(v5);
+        let v7 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v4);
+        let v8 = pavex_tracing::logger(v7, v6).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v8)
+    }
+    async fn handler(
+        v0: &pavex::request::RequestHead,
+        v1: &leptos_pavex::cors::CorsPolicy,
+    ) -> pavex::response::Response {
+        let v2 = leptos_pavex::cors::server_fn_preflight(v0, v1);
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+        v1: &pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let v2 = app::telemetry::response_logger(v0, v1).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    struct Next0<'a, 'b, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: &'a pavex::request::RequestHead,
+        s_1: pavex::request::path::MatchedPathPattern,
+        s_2: &'b leptos_pavex::cors::CorsPolicy,
+        next: fn(
+            &'a pavex::request::RequestHead,
+            pavex::request::path::MatchedPathPattern,
+            &'b leptos_pavex::cors::CorsPolicy,
+        ) -> T,
+    }
+    impl<'a, 'b, T> std::future::IntoFuture for Next0<'a, 'b, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self.next)(self.s_0, self.s_1, self.s_2)
+        }
+    }
+    struct Next1<'a, 'b, 'c, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: &'a pavex::request::RequestHead,
+        s_1: &'b leptos_pavex::cors::CorsPolicy,
+        s_2: &'c pavex_tracing::RootSpan,
+        next: fn(
+            &'a pavex::request::RequestHead,
+            &'b leptos_pavex::cors::CorsPolicy,
+            &'c pavex_tracing::RootSpan,
+        ) -> T,
+    }
+    impl<'a, 'b, 'c, T> std::future::IntoFuture for Next1<'a, 'b, 'c, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self.next)(self.s_0, self.s_1, self.s_2)
+        }
+    }
+}
+pub mod route_2 {
+    pub async fn entrypoint<'a, 'b, 'c, 'd>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'d leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
+        response
+    }
+    async fn stage_1<'a, 'b, 'c, 'd>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'d leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let response = wrapping_1(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
+        response
+    }
+    async fn stage_2<'a, 'b, 'c, 'd, 'e, 'f>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a pavex::request::path::MatchedPathPattern,
+        s_3: &'b pavex::connection::ConnectionInfo,
+        s_4: &'c leptos_pavex::origin::TrustedProxies,
+        s_5: &'d leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'e leptos_pavex::render_config::RenderConfig,
+        s_8: &'f pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let response = handler(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
+        let response = post_processing_0(response, s_8).await;
         response
     }
-    async fn stage_1(
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: pavex::request::path::MatchedPathPattern,
+    async fn wrapping_0(
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: leptos_config::LeptosOptions,
+        v7: &leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let v8 = crate::route_2::Next0 {
+            s_0: v0,
+            s_1: v1,
+            s_2: v2,
+            s_3: v3,
+            s_4: v4,
+            s_5: v5,
+            s_6: v6,
+            s_7: v7,
+            next: stage_1,
+        };
+        let v9 = pavex::middleware::Next::new(v8);
+        let v10 = pavex::middleware::wrap_noop(v9).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v10)
+    }
+    async fn wrapping_1(
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: leptos_config::LeptosOptions,
+        v7: &leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let v8 = pavex::telemetry::ServerRequestId::generate();
+        let v9 = app::telemetry::root_span(&v0, v2, v8);
+        let v10 = crate::route_2::Next1 {
+            s_0: v0,
+            s_1: v1,
+            s_2: &v2,
+            s_3: v3,
+            s_4: v4,
+            s_5: v5,
+            s_6: v6,
+            s_7: v7,
+            s_8: &v9,
+            next: stage_2,
+        };
+        let v11 = pavex::middleware::Next::new(v10);
+        let v12 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v9);
+        let v13 = pavex_tracing::logger(v12, v11).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v13)
+    }
+    async fn handler(
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: &pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: leptos_config::LeptosOptions,
+        v7: &leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let v8 = leptos_pavex::origin::RequestOrigin::extract(&v0, v3, v4, v5);
+        let v9 = app::leptos::additional_context_components(&v0, &v8);
+        let v10 = app::leptos::generate_app(&v9, v6);
+        let v11 = leptos_pavex::render_route(v7, v0, v1, v2, v9, v10).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v11)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+        v1: &pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let v2 = app::telemetry::response_logger(v0, v1).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    struct Next0<'a, 'b, 'c, 'd, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'d leptos_pavex::render_config::RenderConfig,
+        next: fn(
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            pavex::request::path::MatchedPathPattern,
+            &'a pavex::connection::ConnectionInfo,
+            &'b leptos_pavex::origin::TrustedProxies,
+            &'c leptos_pavex::mount::MountPoint,
+            leptos_config::LeptosOptions,
+            &'d leptos_pavex::render_config::RenderConfig,
+        ) -> T,
+    }
+    impl<'a, 'b, 'c, 'd, T> std::future::IntoFuture for Next0<'a, 'b, 'c, 'd, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+                self.s_7,
+            )
+        }
+    }
+    struct Next1<'a, 'b, 'c, 'd, 'e, 'f, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a pavex::request::path::MatchedPathPattern,
+        s_3: &'b pavex::connection::ConnectionInfo,
+        s_4: &'c leptos_pavex::origin::TrustedProxies,
+        s_5: &'d leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'e leptos_pavex::render_config::RenderConfig,
+        s_8: &'f pavex_tracing::RootSpan,
+        next: fn(
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            &'a pavex::request::path::MatchedPathPattern,
+            &'b pavex::connection::ConnectionInfo,
+            &'c leptos_pavex::origin::TrustedProxies,
+            &'d leptos_pavex::mount::MountPoint,
+            leptos_config::LeptosOptions,
+            &'e leptos_pavex::render_config::RenderConfig,
+            &'f pavex_tracing::RootSpan,
+        ) -> T,
+    }
+    impl<'a, 'b, 'c, 'd, 'e, 'f, T> std::future::IntoFuture
+    for Next1<'a, 'b, 'c, 'd, 'e, 'f, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+                self.s_7,
+                self.s_8,
+            )
+        }
+    }
+}
+pub mod route_3 {
+    pub async fn entrypoint<'a, 'b, 'c, 'd>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'d leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
+        response
+    }
+    async fn stage_1<'a, 'b, 'c, 'd>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'d leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let response = wrapping_1(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
+        response
+    }
+    async fn stage_2<'a, 'b, 'c, 'd, 'e, 'f>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a pavex::request::path::MatchedPathPattern,
+        s_3: &'b pavex::connection::ConnectionInfo,
+        s_4: &'c leptos_pavex::origin::TrustedProxies,
+        s_5: &'d leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'e leptos_pavex::render_config::RenderConfig,
+        s_8: &'f pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let response = handler(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
+        let response = post_processing_0(response, s_8).await;
+        response
+    }
+    async fn wrapping_0(
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: leptos_config::LeptosOptions,
+        v7: &leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let v8 = crate::route_3::Next0 {
+            s_0: v0,
+            s_1: v1,
+            s_2: v2,
+            s_3: v3,
+            s_4: v4,
+            s_5: v5,
+            s_6: v6,
+            s_7: v7,
+            next: stage_1,
+        };
+        let v9 = pavex::middleware::Next::new(v8);
+        let v10 = pavex::middleware::wrap_noop(v9).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v10)
+    }
+    async fn wrapping_1(
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: leptos_config::LeptosOptions,
+        v7: &leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let v8 = pavex::telemetry::ServerRequestId::generate();
+        let v9 = app::telemetry::root_span(&v0, v2, v8);
+        let v10 = crate::route_3::Next1 {
+            s_0: v0,
+            s_1: v1,
+            s_2: &v2,
+            s_3: v3,
+            s_4: v4,
+            s_5: v5,
+            s_6: v6,
+            s_7: v7,
+            s_8: &v9,
+            next: stage_2,
+        };
+        let v11 = pavex::middleware::Next::new(v10);
+        let v12 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v9);
+        let v13 = pavex_tracing::logger(v12, v11).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v13)
+    }
+    async fn handler(
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: &pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: leptos_config::LeptosOptions,
+        v7: &leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let v8 = leptos_pavex::origin::RequestOrigin::extract(&v0, v3, v4, v5);
+        let v9 = app::leptos::additional_context_components(&v0, &v8);
+        let v10 = app::leptos::generate_app(&v9, v6);
+        let v11 = leptos_pavex::render_route_head(v7, v0, v1, v2, v9, v10).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v11)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+        v1: &pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let v2 = app::telemetry::response_logger(v0, v1).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    struct Next0<'a, 'b, 'c, 'd, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'d leptos_pavex::render_config::RenderConfig,
+        next: fn(
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            pavex::request::path::MatchedPathPattern,
+            &'a pavex::connection::ConnectionInfo,
+            &'b leptos_pavex::origin::TrustedProxies,
+            &'c leptos_pavex::mount::MountPoint,
+            leptos_config::LeptosOptions,
+            &'d leptos_pavex::render_config::RenderConfig,
+        ) -> T,
+    }
+    impl<'a, 'b, 'c, 'd, T> std::future::IntoFuture for Next0<'a, 'b, 'c, 'd, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+                self.s_7,
+            )
+        }
+    }
+    struct Next1<'a, 'b, 'c, 'd, 'e, 'f, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a pavex::request::path::MatchedPathPattern,
+        s_3: &'b pavex::connection::ConnectionInfo,
+        s_4: &'c leptos_pavex::origin::TrustedProxies,
+        s_5: &'d leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'e leptos_pavex::render_config::RenderConfig,
+        s_8: &'f pavex_tracing::RootSpan,
+        next: fn(
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            &'a pavex::request::path::MatchedPathPattern,
+            &'b pavex::connection::ConnectionInfo,
+            &'c leptos_pavex::origin::TrustedProxies,
+            &'d leptos_pavex::mount::MountPoint,
+            leptos_config::LeptosOptions,
+            &'e leptos_pavex::render_config::RenderConfig,
+            &'f pavex_tracing::RootSpan,
+        ) -> T,
+    }
+    impl<'a, 'b, 'c, 'd, 'e, 'f, T> std::future::IntoFuture
+    for Next1<'a, 'b, 'c, 'd, 'e, 'f, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+                self.s_7,
+                self.s_8,
+            )
+        }
+    }
+}
+pub mod route_4 {
+    pub async fn entrypoint<'a, 'b>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: pavex::request::path::MatchedPathPattern,
+        s_2: &'b leptos_pavex::route_table::PavexRouteTable,
+    ) -> pavex::response::Response {
+        let response = wrapping_0(s_0, s_1, s_2).await;
+        response
+    }
+    async fn stage_1<'a, 'b>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: pavex::request::path::MatchedPathPattern,
+        s_2: &'b leptos_pavex::route_table::PavexRouteTable,
+    ) -> pavex::response::Response {
+        let response = wrapping_1(s_0, s_1, s_2).await;
+        response
+    }
+    async fn stage_2<'a, 'b, 'c>(
+        s_0: &'a pavex::request::path::MatchedPathPattern,
+        s_1: &'b leptos_pavex::route_table::PavexRouteTable,
+        s_2: &'c pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let response = handler(s_1, s_0).await;
+        let response = post_processing_0(response, s_2).await;
+        response
+    }
+    async fn wrapping_0(
+        v0: &pavex::request::RequestHead,
+        v1: pavex::request::path::MatchedPathPattern,
+        v2: &leptos_pavex::route_table::PavexRouteTable,
+    ) -> pavex::response::Response {
+        let v3 = crate::route_4::Next0 {
+            s_0: v0,
+            s_1: v1,
+            s_2: v2,
+            next: stage_1,
+        };
+        let v4 = pavex::middleware::Next::new(v3);
+        let v5 = pavex::middleware::wrap_noop(v4).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v5)
+    }
+    async fn wrapping_1(
+        v0: &pavex::request::RequestHead,
+        v1: pavex::request::path::MatchedPathPattern,
+        v2: &leptos_pavex::route_table::PavexRouteTable,
+    ) -> pavex::response::Response {
+        let v3 = pavex::telemetry::ServerRequestId::generate();
+        let v4 = app::telemetry::root_span(v0, v1, v3);
+        let v5 = crate::route_4::Next1 {
+            s_0: &v1,
+            s_1: v2,
+            s_2: &v4,
+            next: stage_2,
+        };
+        let v6 = pavex::middleware::Next::new(v5);
+        let v7 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v4);
+        let v8 = pavex_tracing::logger(v7, v6).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v8)
+    }
+    async fn handler(
+        v0: &leptos_pavex::route_table::PavexRouteTable,
+        v1: &pavex::request::path::MatchedPathPattern,
+    ) -> pavex::response::Response {
+        let v2 = leptos_pavex::route_options(v0, v1);
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+        v1: &pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let v2 = app::telemetry::response_logger(v0, v1).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    struct Next0<'a, 'b, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: &'a pavex::request::RequestHead,
+        s_1: pavex::request::path::MatchedPathPattern,
+        s_2: &'b leptos_pavex::route_table::PavexRouteTable,
+        next: fn(
+            &'a pavex::request::RequestHead,
+            pavex::request::path::MatchedPathPattern,
+            &'b leptos_pavex::route_table::PavexRouteTable,
+        ) -> T,
+    }
+    impl<'a, 'b, T> std::future::IntoFuture for Next0<'a, 'b, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self.next)(self.s_0, self.s_1, self.s_2)
+        }
+    }
+    struct Next1<'a, 'b, 'c, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: &'a pavex::request::path::MatchedPathPattern,
+        s_1: &'b leptos_pavex::route_table::PavexRouteTable,
+        s_2: &'c pavex_tracing::RootSpan,
+        next: fn(
+            &'a pavex::request::path::MatchedPathPattern,
+            &'b leptos_pavex::route_table::PavexRouteTable,
+            &'c pavex_tracing::RootSpan,
+        ) -> T,
+    }
+    impl<'a, 'b, 'c, T> std::future::IntoFuture for Next1<'a, 'b, 'c, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self.next)(self.s_0, self.s_1, self.s_2)
+        }
+    }
+}
+pub mod route_5 {
+    pub async fn entrypoint<'a, 'b, 'c, 'd>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'d leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
+        response
+    }
+    async fn stage_1<'a, 'b, 'c, 'd>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'d leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let response = wrapping_1(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
+        response
+    }
+    async fn stage_2<'a, 'b, 'c, 'd, 'e, 'f>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a pavex::request::path::MatchedPathPattern,
+        s_3: &'b pavex::connection::ConnectionInfo,
+        s_4: &'c leptos_pavex::origin::TrustedProxies,
+        s_5: &'d leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'e leptos_pavex::render_config::RenderConfig,
+        s_8: &'f pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let response = handler(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
+        let response = post_processing_0(response, s_8).await;
+        response
+    }
+    async fn wrapping_0(
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: leptos_config::LeptosOptions,
+        v7: &leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let v8 = crate::route_5::Next0 {
+            s_0: v0,
+            s_1: v1,
+            s_2: v2,
+            s_3: v3,
+            s_4: v4,
+            s_5: v5,
+            s_6: v6,
+            s_7: v7,
+            next: stage_1,
+        };
+        let v9 = pavex::middleware::Next::new(v8);
+        let v10 = pavex::middleware::wrap_noop(v9).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v10)
+    }
+    async fn wrapping_1(
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: leptos_config::LeptosOptions,
+        v7: &leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let v8 = pavex::telemetry::ServerRequestId::generate();
+        let v9 = app::telemetry::root_span(&v0, v2, v8);
+        let v10 = crate::route_5::Next1 {
+            s_0: v0,
+            s_1: v1,
+            s_2: &v2,
+            s_3: v3,
+            s_4: v4,
+            s_5: v5,
+            s_6: v6,
+            s_7: v7,
+            s_8: &v9,
+            next: stage_2,
+        };
+        let v11 = pavex::middleware::Next::new(v10);
+        let v12 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v9);
+        let v13 = pavex_tracing::logger(v12, v11).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v13)
+    }
+    async fn handler(
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: &pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: leptos_config::LeptosOptions,
+        v7: &leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let v8 = leptos_pavex::origin::RequestOrigin::extract(&v0, v3, v4, v5);
+        let v9 = app::leptos::additional_context_components(&v0, &v8);
+        let v10 = app::leptos::generate_app(&v9, v6);
+        let v11 = leptos_pavex::render_route(v7, v0, v1, v2, v9, v10).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v11)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+        v1: &pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let v2 = app::telemetry::response_logger(v0, v1).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    struct Next0<'a, 'b, 'c, 'd, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'d leptos_pavex::render_config::RenderConfig,
+        next: fn(
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            pavex::request::path::MatchedPathPattern,
+            &'a pavex::connection::ConnectionInfo,
+            &'b leptos_pavex::origin::TrustedProxies,
+            &'c leptos_pavex::mount::MountPoint,
+            leptos_config::LeptosOptions,
+            &'d leptos_pavex::render_config::RenderConfig,
+        ) -> T,
+    }
+    impl<'a, 'b, 'c, 'd, T> std::future::IntoFuture for Next0<'a, 'b, 'c, 'd, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+                self.s_7,
+            )
+        }
+    }
+    struct Next1<'a, 'b, 'c, 'd, 'e, 'f, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a pavex::request::path::MatchedPathPattern,
+        s_3: &'b pavex::connection::ConnectionInfo,
+        s_4: &'c leptos_pavex::origin::TrustedProxies,
+        s_5: &'d leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'e leptos_pavex::render_config::RenderConfig,
+        s_8: &'f pavex_tracing::RootSpan,
+        next: fn(
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            &'a pavex::request::path::MatchedPathPattern,
+            &'b pavex::connection::ConnectionInfo,
+            &'c leptos_pavex::origin::TrustedProxies,
+            &'d leptos_pavex::mount::MountPoint,
+            leptos_config::LeptosOptions,
+            &'e leptos_pavex::render_config::RenderConfig,
+            &'f pavex_tracing::RootSpan,
+        ) -> T,
+    }
+    impl<'a, 'b, 'c, 'd, 'e, 'f, T> std::future::IntoFuture
+    for Next1<'a, 'b, 'c, 'd, 'e, 'f, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+                self.s_7,
+                self.s_8,
+            )
+        }
+    }
+}
+pub mod route_6 {
+    pub async fn entrypoint<'a, 'b, 'c, 'd>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'d leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
+        response
+    }
+    async fn stage_1<'a, 'b, 'c, 'd>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'d leptos_pavex::render_config::RenderConfig,
     ) -> pavex::response::Response {
-        let response = wrapping_1(s_0, s_1, s_2, s_3, s_4).await;
+        let response = wrapping_1(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
         response
     }
-    async fn stage_2<'a, 'b>(
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: &'a pavex::request::path::MatchedPathPattern,
-        s_5: &'b pavex_tracing::RootSpan,
+    async fn stage_2<'a, 'b, 'c, 'd, 'e, 'f>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a pavex::request::path::MatchedPathPattern,
+        s_3: &'b pavex::connection::ConnectionInfo,
+        s_4: &'c leptos_pavex::origin::TrustedProxies,
+        s_5: &'d leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'e leptos_pavex::render_config::RenderConfig,
+        s_8: &'f pavex_tracing::RootSpan,
     ) -> pavex::response::Response {
-        let response = handler(s_0, s_1, s_2, s_3, s_4).await;
-        let response = post_processing_0(response, s_5).await;
+        let response = handler(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
+        let response = post_processing_0(response, s_8).await;
         response
     }
     async fn wrapping_0(
-        v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        v1: pavex::request::RequestHead,
-        v2: pavex::request::body::RawIncomingBody,
-        v3: leptos_config::LeptosOptions,
-        v4: pavex::request::path::MatchedPathPattern,
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: leptos_config::LeptosOptions,
+        v7: &leptos_pavex::render_config::RenderConfig,
     ) -> pavex::response::Response {
-        let v5 = crate::route_1::Next0 {
+        let v8 = crate::route_6::Next0 {
             s_0: v0,
             s_1: v1,
             s_2: v2,
             s_3: v3,
             s_4: v4,
+            s_5: v5,
+            s_6: v6,
+            s_7: v7,
             next: stage_1,
         };
-        let v6 = pavex::middleware::Next::new(v5);
-        let v7 = pavex::middleware::wrap_noop(v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v7)
+        let v9 = pavex::middleware::Next::new(v8);
+        let v10 = pavex::middleware::wrap_noop(v9).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v10)
     }
     async fn wrapping_1(
-        v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        v1: pavex::request::RequestHead,
-        v2: pavex::request::body::RawIncomingBody,
-        v3: leptos_config::LeptosOptions,
-        v4: pavex::request::path::MatchedPathPattern,
-    ) -> pavex::response::Response {
-        let v5 = pavex::telemetry::ServerRequestId::generate();
-        let v6 = app::telemetry::root_span(&v1, v4, v5);
-        let v7 = crate::route_1::Next1 {
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: leptos_config::LeptosOptions,
+        v7: &leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let v8 = pavex::telemetry::ServerRequestId::generate();
+        let v9 = app::telemetry::root_span(&v0, v2, v8);
+        let v10 = crate::route_6::Next1 {
             s_0: v0,
             s_1: v1,
-            s_2: v2,
+            s_2: &v2,
             s_3: v3,
-            s_4: &v4,
-            s_5: &v6,
+            s_4: v4,
+            s_5: v5,
+            s_6: v6,
+            s_7: v7,
+            s_8: &v9,
             next: stage_2,
         };
-        let v8 = pavex::middleware::Next::new(v7);
-        let v9 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v6);
-        let v10 = pavex_tracing::logger(v9, v8).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v10)
+        let v11 = pavex::middleware::Next::new(v10);
+        let v12 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v9);
+        let v13 = pavex_tracing::logger(v12, v11).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v13)
     }
     async fn handler(
-        v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        v1: pavex::request::RequestHead,
-        v2: pavex::request::body::RawIncomingBody,
-        v3: leptos_config::LeptosOptions,
-        v4: &pavex::request::path::MatchedPathPattern,
-    ) -> pavex::response::Response {
-        let v5 = app::leptos::additional_context_components(&v1);
-        let v6 = app::leptos::generate_app(&v5, v3);
-        let v7 = leptos_pavex::render_route(v0, v1, v2, v4, v5, v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v7)
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: &pavex::request::path::MatchedPathPattern,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: leptos_config::LeptosOptions,
+        v7: &leptos_pavex::render_config::RenderConfig,
+    ) -> pavex::response::Response {
+        let v8 = leptos_pavex::origin::RequestOrigin::extract(&v0, v3, v4, v5);
+        let v9 = app::leptos::additional_context_components(&v0, &v8);
+        let v10 = app::leptos::generate_app(&v9, v6);
+        let v11 = leptos_pavex::render_route_head(v7, v0, v1, v2, v9, v10).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v11)
     }
     async fn post_processing_0(
         v0: pavex::response::Response,
@@ -455,149 +1553,162 @@ pub mod route_1 {
         let v2 = app::telemetry::response_logger(v0, v1).await;
         <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
     }
-    struct Next0<T>
+    struct Next0<'a, 'b, 'c, 'd, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: pavex::request::path::MatchedPathPattern,
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::connection::ConnectionInfo,
+        s_4: &'b leptos_pavex::origin::TrustedProxies,
+        s_5: &'c leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'d leptos_pavex::render_config::RenderConfig,
         next: fn(
-            alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
             pavex::request::RequestHead,
             pavex::request::body::RawIncomingBody,
-            leptos_config::LeptosOptions,
             pavex::request::path::MatchedPathPattern,
+            &'a pavex::connection::ConnectionInfo,
+            &'b leptos_pavex::origin::TrustedProxies,
+            &'c leptos_pavex::mount::MountPoint,
+            leptos_config::LeptosOptions,
+            &'d leptos_pavex::render_config::RenderConfig,
         ) -> T,
     }
-    impl<T> std::future::IntoFuture for Next0<T>
+    impl<'a, 'b, 'c, 'd, T> std::future::IntoFuture for Next0<'a, 'b, 'c, 'd, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4)
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+                self.s_7,
+            )
         }
     }
-    struct Next1<'a, 'b, T>
+    struct Next1<'a, 'b, 'c, 'd, 'e, 'f, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: &'a pavex::request::path::MatchedPathPattern,
-        s_5: &'b pavex_tracing::RootSpan,
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a pavex::request::path::MatchedPathPattern,
+        s_3: &'b pavex::connection::ConnectionInfo,
+        s_4: &'c leptos_pavex::origin::TrustedProxies,
+        s_5: &'d leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'e leptos_pavex::render_config::RenderConfig,
+        s_8: &'f pavex_tracing::RootSpan,
         next: fn(
-            alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
             pavex::request::RequestHead,
             pavex::request::body::RawIncomingBody,
-            leptos_config::LeptosOptions,
             &'a pavex::request::path::MatchedPathPattern,
-            &'b pavex_tracing::RootSpan,
+            &'b pavex::connection::ConnectionInfo,
+            &'c leptos_pavex::origin::TrustedProxies,
+            &'d leptos_pavex::mount::MountPoint,
+            leptos_config::LeptosOptions,
+            &'e leptos_pavex::render_config::RenderConfig,
+            &'f pavex_tracing::RootSpan,
         ) -> T,
     }
-    impl<'a, 'b, T> std::future::IntoFuture for Next1<'a, 'b, T>
+    impl<'a, 'b, 'c, 'd, 'e, 'f, T> std::future::IntoFuture
+    for Next1<'a, 'b, 'c, 'd, 'e, 'f, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4, self.s_5)
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+                self.s_7,
+                self.s_8,
+            )
         }
     }
 }
-pub mod route_2 {
-    pub async fn entrypoint(
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: pavex::request::path::MatchedPathPattern,
-    ) -> pavex::response::Response {
-        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4).await;
+pub mod route_7 {
+    pub async fn entrypoint<'a, 'b>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: pavex::request::path::MatchedPathPattern,
+        s_2: &'b leptos_pavex::route_table::PavexRouteTable,
+    ) -> pavex::response::Response {
+        let response = wrapping_0(s_0, s_1, s_2).await;
         response
     }
-    async fn stage_1(
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: pavex::request::path::MatchedPathPattern,
+    async fn stage_1<'a, 'b>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: pavex::request::path::MatchedPathPattern,
+        s_2: &'b leptos_pavex::route_table::PavexRouteTable,
     ) -> pavex::response::Response {
-        let response = wrapping_1(s_0, s_1, s_2, s_3, s_4).await;
+        let response = wrapping_1(s_0, s_1, s_2).await;
         response
     }
-    async fn stage_2<'a, 'b>(
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: &'a pavex::request::path::MatchedPathPattern,
-        s_5: &'b pavex_tracing::RootSpan,
+    async fn stage_2<'a, 'b, 'c>(
+        s_0: &'a pavex::request::path::MatchedPathPattern,
+        s_1: &'b leptos_pavex::route_table::PavexRouteTable,
+        s_2: &'c pavex_tracing::RootSpan,
     ) -> pavex::response::Response {
-        let response = handler(s_0, s_1, s_2, s_3, s_4).await;
-        let response = post_processing_0(response, s_5).await;
+        let response = handler(s_1, s_0).await;
+        let response = post_processing_0(response, s_2).await;
         response
     }
     async fn wrapping_0(
-        v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        v1: pavex::request::RequestHead,
-        v2: pavex::request::body::RawIncomingBody,
-        v3: leptos_config::LeptosOptions,
-        v4: pavex::request::path::MatchedPathPattern,
+        v0: &pavex::request::RequestHead,
+        v1: pavex::request::path::MatchedPathPattern,
+        v2: &leptos_pavex::route_table::PavexRouteTable,
     ) -> pavex::response::Response {
-        let v5 = crate::route_2::Next0 {
+        let v3 = crate::route_7::Next0 {
             s_0: v0,
             s_1: v1,
             s_2: v2,
-            s_3: v3,
-            s_4: v4,
             next: stage_1,
         };
-        let v6 = pavex::middleware::Next::new(v5);
-        let v7 = pavex::middleware::wrap_noop(v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v7)
+        let v4 = pavex::middleware::Next::new(v3);
+        let v5 = pavex::middleware::wrap_noop(v4).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v5)
     }
     async fn wrapping_1(
-        v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        v1: pavex::request::RequestHead,
-        v2: pavex::request::body::RawIncomingBody,
-        v3: leptos_config::LeptosOptions,
-        v4: pavex::request::path::MatchedPathPattern,
-    ) -> pavex::response::Response {
-        let v5 = pavex::telemetry::ServerRequestId::generate();
-        let v6 = app::telemetry::root_span(&v1, v4, v5);
-        let v7 = crate::route_2::Next1 {
-            s_0: v0,
-            s_1: v1,
-            s_2: v2,
-            s_3: v3,
-            s_4: &v4,
-            s_5: &v6,
+        v0: &pavex::request::RequestHead,
+        v1: pavex::request::path::MatchedPathPattern,
+        v2: &leptos_pavex::route_table::PavexRouteTable,
+    ) -> pavex::response::Response {
+        let v3 = pavex::telemetry::ServerRequestId::generate();
+        let v4 = app::telemetry::root_span(v0, v1, v3);
+        let v5 = crate::route_7::Next1 {
+            s_0: &v1,
+            s_1: v2,
+            s_2: &v4,
             next: stage_2,
         };
-        let v8 = pavex::middleware::Next::new(v7);
-        let v9 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v6);
-        let v10 = pavex_tracing::logger(v9, v8).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v10)
+        let v6 = pavex::middleware::Next::new(v5);
+        let v7 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v4);
+        let v8 = pavex_tracing::logger(v7, v6).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v8)
     }
     async fn handler(
-        v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        v1: pavex::request::RequestHead,
-        v2: pavex::request::body::RawIncomingBody,
-        v3: leptos_config::LeptosOptions,
-        v4: &pavex::request::path::MatchedPathPattern,
-    ) -> pavex::response::Response {
-        let v5 = app::leptos::additional_context_components(&v1);
-        let v6 = app::leptos::generate_app(&v5, v3);
-        let v7 = leptos_pavex::render_route(v0, v1, v2, v4, v5, v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v7)
+        v0: &leptos_pavex::route_table::PavexRouteTable,
+        v1: &pavex::request::path::MatchedPathPattern,
+    ) -> pavex::response::Response {
+        let v2 = leptos_pavex::route_options(v0, v1);
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
     }
     async fn post_processing_0(
         v0: pavex::response::Response,
@@ -606,64 +1717,54 @@ pub mod route_2 {
         let v2 = app::telemetry::response_logger(v0, v1).await;
         <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
     }
-    struct Next0<T>
+    struct Next0<'a, 'b, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: pavex::request::path::MatchedPathPattern,
+        s_0: &'a pavex::request::RequestHead,
+        s_1: pavex::request::path::MatchedPathPattern,
+        s_2: &'b leptos_pavex::route_table::PavexRouteTable,
         next: fn(
-            alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-            pavex::request::RequestHead,
-            pavex::request::body::RawIncomingBody,
-            leptos_config::LeptosOptions,
+            &'a pavex::request::RequestHead,
             pavex::request::path::MatchedPathPattern,
+            &'b leptos_pavex::route_table::PavexRouteTable,
         ) -> T,
     }
-    impl<T> std::future::IntoFuture for Next0<T>
+    impl<'a, 'b, T> std::future::IntoFuture for Next0<'a, 'b, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4)
+            (self.next)(self.s_0, self.s_1, self.s_2)
         }
     }
-    struct Next1<'a, 'b, T>
+    struct Next1<'a, 'b, 'c, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: &'a pavex::request::path::MatchedPathPattern,
-        s_5: &'b pavex_tracing::RootSpan,
+        s_0: &'a pavex::request::path::MatchedPathPattern,
+        s_1: &'b leptos_pavex::route_table::PavexRouteTable,
+        s_2: &'c pavex_tracing::RootSpan,
         next: fn(
-            alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-            pavex::request::RequestHead,
-            pavex::request::body::RawIncomingBody,
-            leptos_config::LeptosOptions,
             &'a pavex::request::path::MatchedPathPattern,
-            &'b pavex_tracing::RootSpan,
+            &'b leptos_pavex::route_table::PavexRouteTable,
+            &'c pavex_tracing::RootSpan,
         ) -> T,
     }
-    impl<'a, 'b, T> std::future::IntoFuture for Next1<'a, 'b, T>
+    impl<'a, 'b, 'c, T> std::future::IntoFuture for Next1<'a, 'b, 'c, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4, self.s_5)
+            (self.next)(self.s_0, self.s_1, self.s_2)
         }
     }
 }
-pub mod route_3 {
+pub mod route_8 {
     pub async fn entrypoint<'a>(
         s_0: pavex::request::path::MatchedPathPattern,
         s_1: &'a pavex::request::RequestHead,
@@ -687,7 +1788,7 @@ pub mod route_3 {
         v0: pavex::request::path::MatchedPathPattern,
         v1: &pavex::request::RequestHead,
     ) -> pavex::response::Response {
-        let v2 = crate::route_3::Next0 {
+        let v2 = crate::route_8::Next0 {
             s_0: v0,
             s_1: v1,
             next: stage_1,
@@ -702,7 +1803,7 @@ pub mod route_3 {
     ) -> pavex::response::Response {
         let v2 = pavex::telemetry::ServerRequestId::generate();
         let v3 = app::telemetry::root_span(v1, v0, v2);
-        let v4 = crate::route_3::Next1 {
+        let v4 = crate::route_8::Next1 {
             s_0: &v3,
             next: stage_2,
         };
@@ -761,7 +1862,7 @@ pub mod route_3 {
         }
     }
 }
-pub mod route_4 {
+pub mod route_9 {
     pub async fn entrypoint<'a, 'b, 'c, 'd>(
         s_0: pavex::request::path::RawPathParams<'a, 'b>,
         s_1: &'c pavex::request::RequestHead,
@@ -773,11 +1874,11 @@ pub mod route_4 {
     }
     async fn stage_1<'a, 'b, 'c, 'd>(
         s_0: pavex::request::path::RawPathParams<'a, 'b>,
-        s_1: &'c app::configuration::AppConfig,
+        s_1: &'c pavex::request::RequestHead,
         s_2: pavex::request::path::MatchedPathPattern,
-        s_3: &'d pavex::request::RequestHead,
+        s_3: &'d app::configuration::AppConfig,
     ) -> pavex::response::Response {
-        let response = wrapping_1(s_0, s_3, s_2, s_1).await;
+        let response = wrapping_1(s_0, s_1, s_2, s_3).await;
         response
     }
     async fn stage_2<'a, 'b, 'c, 'd>(
@@ -795,11 +1896,11 @@ pub mod route_4 {
         v2: pavex::request::path::MatchedPathPattern,
         v3: &app::configuration::AppConfig,
     ) -> pavex::response::Response {
-        let v4 = crate::route_4::Next0 {
+        let v4 = crate::route_9::Next0 {
             s_0: v0,
-            s_1: v3,
+            s_1: v1,
             s_2: v2,
-            s_3: v1,
+            s_3: v3,
             next: stage_1,
         };
         let v5 = pavex::middleware::Next::new(v4);
@@ -814,7 +1915,7 @@ pub mod route_4 {
     ) -> pavex::response::Response {
         let v4 = pavex::telemetry::ServerRequestId::generate();
         let v5 = app::telemetry::root_span(v1, v2, v4);
-        let v6 = crate::route_4::Next1 {
+        let v6 = crate::route_9::Next1 {
             s_0: &v5,
             s_1: v0,
             s_2: v3,
@@ -864,14 +1965,14 @@ pub mod route_4 {
         T: std::future::Future<Output = pavex::response::Response>,
     {
         s_0: pavex::request::path::RawPathParams<'a, 'b>,
-        s_1: &'c app::configuration::AppConfig,
+        s_1: &'c pavex::request::RequestHead,
         s_2: pavex::request::path::MatchedPathPattern,
-        s_3: &'d pavex::request::RequestHead,
+        s_3: &'d app::configuration::AppConfig,
         next: fn(
             pavex::request::path::RawPathParams<'a, 'b>,
-            &'c app::configuration::AppConfig,
+            &'c pavex::request::RequestHead,
             pavex::request::path::MatchedPathPattern,
-            &'d pavex::request::RequestHead,
+            &'d app::configuration::AppConfig,
         ) -> T,
     }
     impl<'a, 'b, 'c, 'd, T> std::future::IntoFuture for Next0<'a, 'b, 'c, 'd, T>
@@ -908,85 +2009,122 @@ pub mod route_4 {
         }
     }
 }
-pub mod route_5 {
-    pub async fn entrypoint<'a, 'b, 'c>(
-        s_0: pavex::request::path::RawPathParams<'a, 'b>,
-        s_1: pavex::request::path::MatchedPathPattern,
-        s_2: &'c pavex::request::RequestHead,
+pub mod route_10 {
+    pub async fn entrypoint<'a, 'b, 'c, 'd, 'e>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::router::AllowedMethods,
+        s_4: &'b pavex::connection::ConnectionInfo,
+        s_5: &'c leptos_pavex::origin::TrustedProxies,
+        s_6: &'d leptos_pavex::mount::MountPoint,
+        s_7: leptos_config::LeptosOptions,
+        s_8: &'e leptos_pavex::render_config::RenderConfig,
     ) -> pavex::response::Response {
-        let response = wrapping_0(s_0, s_1, s_2).await;
+        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7, s_8).await;
         response
     }
-    async fn stage_1<'a, 'b, 'c>(
-        s_0: pavex::request::path::RawPathParams<'a, 'b>,
-        s_1: pavex::request::path::MatchedPathPattern,
-        s_2: &'c pavex::request::RequestHead,
+    async fn stage_1<'a, 'b, 'c, 'd, 'e>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::router::AllowedMethods,
+        s_4: &'b pavex::connection::ConnectionInfo,
+        s_5: &'c leptos_pavex::origin::TrustedProxies,
+        s_6: &'d leptos_pavex::mount::MountPoint,
+        s_7: leptos_config::LeptosOptions,
+        s_8: &'e leptos_pavex::render_config::RenderConfig,
     ) -> pavex::response::Response {
-        let response = wrapping_1(s_0, s_1, s_2).await;
+        let response = wrapping_1(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7, s_8).await;
         response
     }
-    async fn stage_2<'a, 'b, 'c>(
-        s_0: pavex::request::path::RawPathParams<'a, 'b>,
-        s_1: &'c pavex_tracing::RootSpan,
+    async fn stage_2<'a, 'b, 'c, 'd, 'e, 'f>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a pavex::router::AllowedMethods,
+        s_3: &'b pavex::connection::ConnectionInfo,
+        s_4: &'c leptos_pavex::origin::TrustedProxies,
+        s_5: &'d leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'e leptos_pavex::render_config::RenderConfig,
+        s_8: &'f pavex_tracing::RootSpan,
     ) -> pavex::response::Response {
-        let response = handler(s_0, s_1).await;
-        let response = post_processing_0(response, s_1).await;
+        let response = handler(s_0, s_1, s_2, s_3, s_4, s_5, s_6, s_7).await;
+        let response = post_processing_0(response, s_8).await;
         response
     }
     async fn wrapping_0(
-        v0: pavex::request::path::RawPathParams<'_, '_>,
-        v1: pavex::request::path::MatchedPathPattern,
-        v2: &pavex::request::RequestHead,
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &pavex::router::AllowedMethods,
+        v4: &pavex::connection::ConnectionInfo,
+        v5: &leptos_pavex::origin::TrustedProxies,
+        v6: &leptos_pavex::mount::MountPoint,
+        v7: leptos_config::LeptosOptions,
+        v8: &leptos_pavex::render_config::RenderConfig,
     ) -> pavex::response::Response {
-        let v3 = crate::route_5::Next0 {
+        let v9 = crate::route_10::Next0 {
             s_0: v0,
             s_1: v1,
             s_2: v2,
+            s_3: v3,
+            s_4: v4,
+            s_5: v5,
+            s_6: v6,
+            s_7: v7,
+            s_8: v8,
             next: stage_1,
         };
-        let v4 = pavex::middleware::Next::new(v3);
-        let v5 = pavex::middleware::wrap_noop(v4).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v5)
+        let v10 = pavex::middleware::Next::new(v9);
+        let v11 = pavex::middleware::wrap_noop(v10).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v11)
     }
     async fn wrapping_1(
-        v0: pavex::request::path::RawPathParams<'_, '_>,
-        v1: pavex::request::path::MatchedPathPattern,
-        v2: &pavex::request::RequestHead,
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &pavex::router::AllowedMethods,
+        v4: &pavex::connection::ConnectionInfo,
+        v5: &leptos_pavex::origin::TrustedProxies,
+        v6: &leptos_pavex::mount::MountPoint,
+        v7: leptos_config::LeptosOptions,
+        v8: &leptos_pavex::render_config::RenderConfig,
     ) -> pavex::response::Response {
-        let v3 = pavex::telemetry::ServerRequestId::generate();
-        let v4 = app::telemetry::root_span(v2, v1, v3);
-        let v5 = crate::route_5::Next1 {
+        let v9 = pavex::telemetry::ServerRequestId::generate();
+        let v10 = app::telemetry::root_span(&v0, v2, v9);
+        let v11 = crate::route_10::Next1 {
             s_0: v0,
-            s_1: &v4,
+            s_1: v1,
+            s_2: v3,
+            s_3: v4,
+            s_4: v5,
+            s_5: v6,
+            s_6: v7,
+            s_7: v8,
+            s_8: &v10,
             next: stage_2,
         };
-        let v6 = pavex::middleware::Next::new(v5);
-        let v7 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v4);
-        let v8 = pavex_tracing::logger(v7, v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v8)
+        let v12 = pavex::middleware::Next::new(v11);
+        let v13 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v10);
+        let v14 = pavex_tracing::logger(v13, v12).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v14)
     }
     async fn handler(
-        v0: pavex::request::path::RawPathParams<'_, '_>,
-        v1: &pavex_tracing::RootSpan,
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: &pavex::router::AllowedMethods,
+        v3: &pavex::connection::ConnectionInfo,
+        v4: &leptos_pavex::origin::TrustedProxies,
+        v5: &leptos_pavex::mount::MountPoint,
+        v6: leptos_config::LeptosOptions,
+        v7: &leptos_pavex::render_config::RenderConfig,
     ) -> pavex::response::Response {
-        let v2 = pavex::request::path::PathParams::extract(v0);
-        let v3 = match v2 {
-            Ok(ok) => ok,
-            Err(v3) => {
-                return {
-                    let v4 = pavex::request::path::errors::ExtractPathParamsError::into_response(
-                        &v3,
-                    );
-                    let v5 = pavex::Error::new(v3);
-                    app::telemetry::error_logger(&v5, v1).await;
-                    <pavex::response::Response as pavex::response::IntoResponse>::into_response(
-                        v4,
-                    )
-                };
-            }
-        };
-        let v4 = leptos_pavex::file_helpers::serve_files(&v3);
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v4)
+        let v8 = leptos_pavex::origin::RequestOrigin::extract(&v0, v3, v4, v5);
+        let v9 = app::leptos::additional_context_components(&v0, &v8);
+        let v10 = app::leptos::generate_app(&v9, v6);
+        let v11 = leptos_pavex::render_fallback(v7, v0, v1, v2, v9, v10).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v11)
     }
     async fn post_processing_0(
         v0: pavex::response::Response,
@@ -995,158 +2133,97 @@ pub mod route_5 {
         let v2 = app::telemetry::response_logger(v0, v1).await;
         <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
     }
-    struct Next0<'a, 'b, 'c, T>
+    struct Next0<'a, 'b, 'c, 'd, 'e, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: pavex::request::path::RawPathParams<'a, 'b>,
-        s_1: pavex::request::path::MatchedPathPattern,
-        s_2: &'c pavex::request::RequestHead,
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a pavex::router::AllowedMethods,
+        s_4: &'b pavex::connection::ConnectionInfo,
+        s_5: &'c leptos_pavex::origin::TrustedProxies,
+        s_6: &'d leptos_pavex::mount::MountPoint,
+        s_7: leptos_config::LeptosOptions,
+        s_8: &'e leptos_pavex::render_config::RenderConfig,
         next: fn(
-            pavex::request::path::RawPathParams<'a, 'b>,
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
             pavex::request::path::MatchedPathPattern,
-            &'c pavex::request::RequestHead,
-        ) -> T,
-    }
-    impl<'a, 'b, 'c, T> std::future::IntoFuture for Next0<'a, 'b, 'c, T>
-    where
-        T: std::future::Future<Output = pavex::response::Response>,
-    {
-        type Output = pavex::response::Response;
-        type IntoFuture = T;
-        fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2)
-        }
-    }
-    struct Next1<'a, 'b, 'c, T>
-    where
-        T: std::future::Future<Output = pavex::response::Response>,
-    {
-        s_0: pavex::request::path::RawPathParams<'a, 'b>,
-        s_1: &'c pavex_tracing::RootSpan,
-        next: fn(
-            pavex::request::path::RawPathParams<'a, 'b>,
-            &'c pavex_tracing::RootSpan,
+            &'a pavex::router::AllowedMethods,
+            &'b pavex::connection::ConnectionInfo,
+            &'c leptos_pavex::origin::TrustedProxies,
+            &'d leptos_pavex::mount::MountPoint,
+            leptos_config::LeptosOptions,
+            &'e leptos_pavex::render_config::RenderConfig,
         ) -> T,
     }
-    impl<'a, 'b, 'c, T> std::future::IntoFuture for Next1<'a, 'b, 'c, T>
+    impl<'a, 'b, 'c, 'd, 'e, T> std::future::IntoFuture for Next0<'a, 'b, 'c, 'd, 'e, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1)
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+                self.s_7,
+                self.s_8,
+            )
         }
     }
-}
-pub mod route_6 {
-    pub async fn entrypoint<'a, 'b>(
-        s_0: &'a pavex::request::RequestHead,
-        s_1: pavex::request::path::MatchedPathPattern,
-        s_2: &'b pavex::router::AllowedMethods,
-    ) -> pavex::response::Response {
-        let response = wrapping_0(s_0, s_1, s_2).await;
-        response
-    }
-    async fn stage_1<'a, 'b>(
-        s_0: &'a pavex::router::AllowedMethods,
-        s_1: pavex::request::path::MatchedPathPattern,
-        s_2: &'b pavex::request::RequestHead,
-    ) -> pavex::response::Response {
-        let response = wrapping_1(s_2, s_1, s_0).await;
-        response
-    }
-    async fn stage_2<'a, 'b>(
-        s_0: &'a pavex::router::AllowedMethods,
-        s_1: &'b pavex_tracing::RootSpan,
-    ) -> pavex::response::Response {
-        let response = handler(s_0).await;
-        let response = post_processing_0(response, s_1).await;
-        response
-    }
-    async fn wrapping_0(
-        v0: &pavex::request::RequestHead,
-        v1: pavex::request::path::MatchedPathPattern,
-        v2: &pavex::router::AllowedMethods,
-    ) -> pavex::response::Response {
-        let v3 = crate::route_6::Next0 {
-            s_0: v2,
-            s_1: v1,
-            s_2: v0,
-            next: stage_1,
-        };
-        let v4 = pavex::middleware::Next::new(v3);
-        let v5 = pavex::middleware::wrap_noop(v4).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v5)
-    }
-    async fn wrapping_1(
-        v0: &pavex::request::RequestHead,
-        v1: pavex::request::path::MatchedPathPattern,
-        v2: &pavex::router::AllowedMethods,
-    ) -> pavex::response::Response {
-        let v3 = pavex::telemetry::ServerRequestId::generate();
-        let v4 = app::telemetry::root_span(v0, v1, v3);
-        let v5 = crate::route_6::Next1 {
-            s_0: v2,
-            s_1: &v4,
-            next: stage_2,
-        };
-        let v6 = pavex::middleware::Next::new(v5);
-        let v7 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v4);
-        let v8 = pavex_tracing::logger(v7, v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v8)
-    }
-    async fn handler(v0: &pavex::router::AllowedMethods) -> pavex::response::Response {
-        let v1 = pavex::router::default_fallback(v0).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v1)
-    }
-    async fn post_processing_0(
-        v0: pavex::response::Response,
-        v1: &pavex_tracing::RootSpan,
-    ) -> pavex::response::Response {
-        let v2 = app::telemetry::response_logger(v0, v1).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
-    }
-    struct Next0<'a, 'b, T>
+    struct Next1<'a, 'b, 'c, 'd, 'e, 'f, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: &'a pavex::router::AllowedMethods,
-        s_1: pavex::request::path::MatchedPathPattern,
-        s_2: &'b pavex::request::RequestHead,
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a pavex::router::AllowedMethods,
+        s_3: &'b pavex::connection::ConnectionInfo,
+        s_4: &'c leptos_pavex::origin::TrustedProxies,
+        s_5: &'d leptos_pavex::mount::MountPoint,
+        s_6: leptos_config::LeptosOptions,
+        s_7: &'e leptos_pavex::render_config::RenderConfig,
+        s_8: &'f pavex_tracing::RootSpan,
         next: fn(
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
             &'a pavex::router::AllowedMethods,
-            pavex::request::path::MatchedPathPattern,
-            &'b pavex::request::RequestHead,
+            &'b pavex::connection::ConnectionInfo,
+            &'c leptos_pavex::origin::TrustedProxies,
+            &'d leptos_pavex::mount::MountPoint,
+            leptos_config::LeptosOptions,
+            &'e leptos_pavex::render_config::RenderConfig,
+            &'f pavex_tracing::RootSpan,
         ) -> T,
     }
-    impl<'a, 'b, T> std::future::IntoFuture for Next0<'a, 'b, T>
-    where
-        T: std::future::Future<Output = pavex::response::Response>,
-    {
-        type Output = pavex::response::Response;
-        type IntoFuture = T;
-        fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2)
-        }
-    }
-    struct Next1<'a, 'b, T>
-    where
-        T: std::future::Future<Output = pavex::response::Response>,
-    {
-        s_0: &'a pavex::router::AllowedMethods,
-        s_1: &'b pavex_tracing::RootSpan,
-        next: fn(&'a pavex::router::AllowedMethods, &'b pavex_tracing::RootSpan) -> T,
-    }
-    impl<'a, 'b, T> std::future::IntoFuture for Next1<'a, 'b, T>
+    impl<'a, 'b, 'c, 'd, 'e, 'f, T> std::future::IntoFuture
+    for Next1<'a, 'b, 'c, 'd, 'e, 'f, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1)
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+                self.s_7,
+                self.s_8,
+            )
         }
     }
 }
@@ -0,0 +1,54 @@
+use pavex::request::body::BodySizeLimit;
+use std::collections::HashMap;
+
+/// The size limits for the bodies of server function requests. Requests over the limit get a
+/// `413 Payload Too Large`.
+///
+/// Server functions use the `BodySizeLimit` registered in the blueprint, like Pavex's `ApiKit`
+/// does, unless they have a limit of their own. An upload can be let through without raising the
+/// limit of every other server function, with the path it's registered at:
+/// ```rust,ignore
+/// pub fn server_fn_body_limits() -> ServerFnBodyLimits {
+///     ServerFnBodyLimits::new().with_limit(
+///         UploadAvatar::PATH,
+///         BodySizeLimit::Enabled { max_size: 20.megabytes() },
+///     )
+/// }
+///
+/// kit.with_server_fn_body_limits(Constructor::singleton(f!(crate::server_fn_body_limits)))
+/// ```
+/// The limit holds for streamed bodies too, which end with an error once they go over it.
+#[derive(Clone, Debug, Default)]
+pub struct ServerFnBodyLimits {
+    default: Option<BodySizeLimit>,
+    limits: HashMap<String, BodySizeLimit>,
+}
+
+impl ServerFnBodyLimits {
+    /// Use the blueprint's `BodySizeLimit` for every server function.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use this limit for the server functions without one of their own, instead of the
+    /// blueprint's `BodySizeLimit`.
+    pub fn with_default_limit(mut self, limit: BodySizeLimit) -> Self {
+        self.default = Some(limit);
+        self
+    }
+
+    /// Use this limit for the server function at a path, which is its `ServerFn::PATH`.
+    pub fn with_limit(mut self, path: impl Into<String>, limit: BodySizeLimit) -> Self {
+        self.limits.insert(path.into(), limit);
+        self
+    }
+
+    /// The limit for the server function at a path, falling back to the blueprint's.
+    pub fn limit_for(&self, path: &str, blueprint_limit: BodySizeLimit) -> BodySizeLimit {
+        self.limits
+            .get(path)
+            .or(self.default.as_ref())
+            .copied()
+            .unwrap_or(blueprint_limit)
+    }
+}
@@ -0,0 +1,413 @@
+use bytes::Bytes;
+use dashmap::DashMap;
+use http_body_util::BodyExt;
+use pavex::http::header::{CACHE_CONTROL, SET_COOKIE};
+use pavex::http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use pavex::request::RequestHead;
+use pavex::response::body::raw::Full;
+use pavex::response::Response;
+use std::fs;
+use std::io::{self, BufRead, Read};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Identifies a rendered response in a [`ResponseCache`]. Two requests share a cached response
+/// if they have the same path, the same query and the same values for every Vary header.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    path: String,
+    query: String,
+    vary: Vec<(HeaderName, Option<HeaderValue>)>,
+}
+
+impl CacheKey {
+    /// Build the key for a request, taking the values of the `vary` headers into account.
+    pub fn new(req_head: &RequestHead, vary: &[HeaderName]) -> Self {
+        Self {
+            path: req_head.target.path().to_string(),
+            query: req_head.target.query().unwrap_or_default().to_string(),
+            vary: vary
+                .iter()
+                .map(|name| (name.clone(), req_head.headers.get(name).cloned()))
+                .collect(),
+        }
+    }
+
+    /// The path of the request.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The query string of the request, or an empty string if it had none.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// The key written on a single line, to tell it apart from the keys whose hash collides with
+    /// it.
+    fn to_line(&self) -> String {
+        let vary = self
+            .vary
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_ref().map(HeaderValue::as_bytes)))
+            .collect::<Vec<_>>();
+        serde_json::to_string(&(&self.path, &self.query, vary))
+            .expect("Failed to serialize the cache key")
+    }
+}
+
+/// A rendered response, along with when it stops being valid.
+#[derive(Clone, Debug)]
+pub struct CachedResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+    pub expires_at: SystemTime,
+}
+
+impl CachedResponse {
+    /// Whether the response is past its expiry.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= SystemTime::now()
+    }
+
+    /// Build a Pavex Response out of the cached one.
+    pub fn to_response(&self) -> Response {
+        let mut res = Response::new(self.status).set_raw_body(Full::new(self.body.clone()));
+        res.headers_mut().extend(self.headers.clone());
+        res
+    }
+}
+
+/// A store for rendered responses. leptos_pavex comes with [`MemoryCache`] and
+/// [`FileSystemCache`], but you can implement it for any other storage.
+pub trait ResponseCache: Send + Sync {
+    /// Get the response stored for a key, if there is one. Implementations don't need to check
+    /// whether it has expired.
+    fn get(&self, key: &CacheKey) -> Option<CachedResponse>;
+
+    /// Store the response for a key, replacing any previous one.
+    fn insert(&self, key: CacheKey, response: CachedResponse);
+
+    /// Remove the response stored for a key.
+    fn remove(&self, key: &CacheKey);
+}
+
+/// A [`ResponseCache`] that keeps responses in memory.
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    entries: DashMap<CacheKey, CachedResponse>,
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ResponseCache for MemoryCache {
+    fn get(&self, key: &CacheKey) -> Option<CachedResponse> {
+        self.entries.get(key).map(|entry| entry.clone())
+    }
+
+    fn insert(&self, key: CacheKey, response: CachedResponse) {
+        self.entries.insert(key, response);
+    }
+
+    fn remove(&self, key: &CacheKey) {
+        self.entries.remove(key);
+    }
+}
+
+/// A [`ResponseCache`] that keeps each response in its own file inside a directory, so that they
+/// survive restarts.
+#[derive(Debug, Clone)]
+pub struct FileSystemCache {
+    dir: PathBuf,
+}
+
+impl FileSystemCache {
+    /// Store responses in `dir`, which is created if it doesn't exist.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn file_path(&self, key: &CacheKey) -> PathBuf {
+        // FNV-1a is fixed by its spec, so the same key maps to the same file across restarts and
+        // Rust versions, unlike std's hashers
+        let hash = key
+            .to_line()
+            .bytes()
+            .fold(0xcbf29ce484222325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+            });
+        self.dir.join(format!("{hash:016x}"))
+    }
+
+    fn read(&self, key: &CacheKey) -> Result<CachedResponse, io::Error> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+        let mut file = io::BufReader::new(fs::File::open(self.file_path(key))?);
+
+        // Keys whose hashes collide share a file, which only holds the response of the last one
+        let mut line = String::new();
+        file.read_line(&mut line)?;
+        if line.trim_end() != key.to_line() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "cached for another key",
+            ));
+        }
+        line.clear();
+        file.read_line(&mut line)?;
+        let expires_at = line
+            .trim_end()
+            .parse()
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+            .map_err(|_| invalid("invalid expiry"))?;
+        line.clear();
+        file.read_line(&mut line)?;
+        let status = line
+            .trim_end()
+            .parse::<u16>()
+            .ok()
+            .and_then(|status| StatusCode::from_u16(status).ok())
+            .ok_or_else(|| invalid("invalid status"))?;
+
+        let mut headers = HeaderMap::new();
+        loop {
+            line.clear();
+            file.read_line(&mut line)?;
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            let (name, value) = header
+                .split_once(": ")
+                .ok_or_else(|| invalid("invalid header"))?;
+            headers.append(
+                HeaderName::try_from(name).map_err(|_| invalid("invalid header name"))?,
+                HeaderValue::try_from(value).map_err(|_| invalid("invalid header value"))?,
+            );
+        }
+
+        let mut body = Vec::new();
+        file.read_to_end(&mut body)?;
+        Ok(CachedResponse {
+            status,
+            headers,
+            body: body.into(),
+            expires_at,
+        })
+    }
+
+    fn write(&self, key: &CacheKey, response: &CachedResponse) -> Result<(), io::Error> {
+        fs::create_dir_all(&self.dir)?;
+        let expires_at = response
+            .expires_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut contents = format!(
+            "{}\n{expires_at}\n{}\n",
+            key.to_line(),
+            response.status.as_u16()
+        )
+        .into_bytes();
+        for (name, value) in response.headers.iter() {
+            // header values that aren't text can't be read back from their line, and the response
+            // would be served without them
+            let value = value.to_str().map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("the value of the {name} header isn't text"),
+                )
+            })?;
+            contents.extend_from_slice(format!("{}: {value}\n", name.as_str()).as_bytes());
+        }
+        contents.push(b'\n');
+        contents.extend_from_slice(&response.body);
+
+        // write to a file next to the entry and rename it in place, so that readers never see a
+        // partly written response
+        static WRITES: AtomicU64 = AtomicU64::new(0);
+        let path = self.file_path(key);
+        let tmp_path = path.with_extension(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp_path, contents)
+            .and_then(|()| fs::rename(&tmp_path, &path))
+            .inspect_err(|_| _ = fs::remove_file(&tmp_path))
+    }
+}
+
+impl ResponseCache for FileSystemCache {
+    fn get(&self, key: &CacheKey) -> Option<CachedResponse> {
+        self.read(key).ok()
+    }
+
+    fn insert(&self, key: CacheKey, response: CachedResponse) {
+        if let Err(e) = self.write(&key, &response) {
+            // the response previously stored for the key is no longer the one to serve
+            self.remove(&key);
+            tracing::warn!(
+                "Failed to write the cached response for {}: {e}",
+                key.path()
+            );
+        }
+    }
+
+    fn remove(&self, key: &CacheKey) {
+        _ = fs::remove_file(self.file_path(key));
+    }
+}
+
+/// How long a response can be cached for, according to its `Cache-Control` header. Responses
+/// without a `max-age` or `s-maxage`, or marked `no-store`, `no-cache` or `private`, can't be
+/// cached.
+pub fn cache_lifetime(headers: &HeaderMap) -> Option<Duration> {
+    let mut max_age = None;
+    let mut s_maxage = None;
+    for directive in headers
+        .get_all(CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|directive| directive.trim().to_ascii_lowercase())
+    {
+        match directive.split_once('=') {
+            Some(("max-age", secs)) => max_age = secs.trim_matches('"').parse().ok(),
+            Some(("s-maxage", secs)) => s_maxage = secs.trim_matches('"').parse().ok(),
+            None if matches!(directive.as_str(), "no-store" | "no-cache" | "private") => {
+                return None
+            }
+            _ => {}
+        }
+    }
+    s_maxage
+        .or(max_age)
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+}
+
+/// Get the cached response for a request, if there's one that hasn't expired.
+pub(crate) fn lookup(cache: &dyn ResponseCache, key: &CacheKey) -> Option<Response> {
+    let cached = cache.get(key)?;
+    if cached.is_expired() {
+        cache.remove(key);
+        return None;
+    }
+    Some(cached.to_response())
+}
+
+/// Store a rendered response in the cache if its `Cache-Control` header allows it, returning a
+/// response with the same contents. Responses setting cookies are never stored, since the cookies
+/// belong to the visitor the response was rendered for.
+pub(crate) async fn store(cache: &dyn ResponseCache, key: CacheKey, res: Response) -> Response {
+    if res.status() != StatusCode::OK || res.headers().contains_key(SET_COOKIE) {
+        return res;
+    }
+    let Some(lifetime) = cache_lifetime(res.headers()) else {
+        return res;
+    };
+
+    let status = res.status();
+    let headers = res.headers().clone();
+    let body = match res.into_parts().1.collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(e) => {
+            tracing::error!("Failed to render {} for the cache: {e}", key.path());
+            return Response::internal_server_error();
+        }
+    };
+    let cached = CachedResponse {
+        status,
+        headers,
+        body,
+        expires_at: SystemTime::now() + lifetime,
+    };
+    let res = cached.to_response();
+    cache.insert(key, cached);
+    res
+}
+
+/// Only GET requests are served from the cache.
+pub(crate) fn is_cacheable(req_head: &RequestHead) -> bool {
+    req_head.method == Method::GET
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pavex::http::header::{CONTENT_TYPE, VARY};
+
+    fn cache(name: &str) -> FileSystemCache {
+        let dir = std::env::temp_dir().join(format!("leptos_pavex_cache_{name}"));
+        _ = fs::remove_dir_all(&dir);
+        FileSystemCache::new(dir)
+    }
+
+    fn key(path: &str) -> CacheKey {
+        CacheKey {
+            path: path.to_string(),
+            query: "page=2".to_string(),
+            vary: vec![(VARY, Some(HeaderValue::from_static("accept")))],
+        }
+    }
+
+    fn response(headers: HeaderMap) -> CachedResponse {
+        CachedResponse {
+            status: StatusCode::OK,
+            headers,
+            body: Bytes::from_static(b"<html>\n\n<body></body></html>"),
+            expires_at: UNIX_EPOCH + Duration::from_secs(4_000_000_000),
+        }
+    }
+
+    #[test]
+    fn responses_are_read_back_as_written() {
+        let cache = cache("round_trip");
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/html"));
+        headers.append(CACHE_CONTROL, HeaderValue::from_static("max-age=60"));
+        headers.append(CACHE_CONTROL, HeaderValue::from_static("public"));
+        cache.insert(key("/about"), response(headers.clone()));
+
+        let cached = cache.get(&key("/about")).unwrap();
+        assert_eq!(cached.status, StatusCode::OK);
+        assert_eq!(cached.headers, headers);
+        assert_eq!(cached.body, response(headers).body);
+        assert_eq!(
+            cached.expires_at,
+            UNIX_EPOCH + Duration::from_secs(4_000_000_000)
+        );
+        assert!(cache.get(&key("/contact")).is_none());
+        assert_eq!(
+            fs::read_dir(&cache.dir).unwrap().count(),
+            1,
+            "the temporary file is renamed into place"
+        );
+    }
+
+    #[test]
+    fn responses_with_binary_headers_are_not_stored() {
+        let cache = cache("binary_headers");
+        cache.insert(key("/about"), response(HeaderMap::new()));
+        let mut headers = HeaderMap::new();
+        headers.insert("x-binary", HeaderValue::from_bytes(b"\xff").unwrap());
+        cache.insert(key("/about"), response(headers));
+        assert!(cache.get(&key("/about")).is_none());
+    }
+
+    #[test]
+    fn file_names_do_not_change_across_builds() {
+        let cache = FileSystemCache::new("/cache");
+        assert_eq!(
+            cache.file_path(&key("/about")),
+            PathBuf::from("/cache/1e081a7f934893cf")
+        );
+    }
+}
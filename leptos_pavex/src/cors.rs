@@ -0,0 +1,350 @@
+use leptos::server_fn::redirect::REDIRECT_HEADER;
+use pavex::http::header::{
+    ACCEPT, ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
+    ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS,
+    ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_METHOD, CONTENT_TYPE, ORIGIN, VARY,
+};
+use pavex::http::{HeaderMap, HeaderName, HeaderValue, Method};
+use pavex::request::RequestHead;
+use pavex::response::Response;
+use std::time::Duration;
+
+/// Which origins other than the server's own are allowed to call the server functions, and how.
+///
+/// By default, no other origin is allowed: the responses only carry `Vary: origin`, and browsers
+/// keep the scripts of other websites from reading them. It only covers the server functions,
+/// since the pages are navigated to rather than fetched. To let a frontend served from another
+/// origin call them with the visitor's cookies, swap the constructor in `LeptosKit`:
+/// ```rust,ignore
+/// pub fn cors_policy() -> CorsPolicy {
+///     CorsPolicy::new()
+///         .allow_origin("https://example.com")
+///         .allow_credentials()
+/// }
+///
+/// kit.with_cors_policy(Constructor::singleton(f!(crate::cors_policy)))
+/// ```
+#[derive(Clone, Debug)]
+pub struct CorsPolicy {
+    origins: AllowedOrigins,
+    methods: Vec<Method>,
+    headers: Vec<HeaderName>,
+    exposed_headers: Vec<HeaderName>,
+    credentials: bool,
+    max_age: Option<Duration>,
+}
+
+/// The origins allowed by a [`CorsPolicy`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AllowedOrigins {
+    /// Every origin.
+    Any,
+    /// Only these origins, written like `https://example.com`.
+    List(Vec<String>),
+}
+
+impl Default for CorsPolicy {
+    fn default() -> Self {
+        Self {
+            origins: AllowedOrigins::List(Vec::new()),
+            methods: vec![
+                Method::GET,
+                Method::POST,
+                Method::PUT,
+                Method::DELETE,
+                Method::PATCH,
+            ],
+            headers: vec![CONTENT_TYPE, ACCEPT],
+            exposed_headers: vec![HeaderName::from_static(REDIRECT_HEADER)],
+            credentials: false,
+            max_age: None,
+        }
+    }
+}
+
+impl CorsPolicy {
+    /// Allow no other origin.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow every origin, without credentials.
+    ///
+    /// # Panics
+    /// Panics if credentials are allowed, see [`CorsPolicy::allow_credentials`].
+    #[track_caller]
+    pub fn allow_any_origin(mut self) -> Self {
+        assert!(
+            !self.credentials,
+            "A CorsPolicy can't allow credentials from any origin, list the origins instead"
+        );
+        self.origins = AllowedOrigins::Any;
+        self
+    }
+
+    /// Allow an origin, written like `https://example.com`.
+    pub fn allow_origin(mut self, origin: impl Into<String>) -> Self {
+        match &mut self.origins {
+            AllowedOrigins::Any => {}
+            AllowedOrigins::List(origins) => origins.push(origin.into()),
+        }
+        self
+    }
+
+    /// Set the methods other origins can call the server functions with. Defaults to the ones
+    /// server functions can be registered for.
+    pub fn allow_methods(mut self, methods: impl IntoIterator<Item = Method>) -> Self {
+        self.methods = methods.into_iter().collect();
+        self
+    }
+
+    /// Allow other origins to send a request header, on top of `Content-Type` and `Accept`.
+    pub fn allow_header(mut self, header: HeaderName) -> Self {
+        self.headers.push(header);
+        self
+    }
+
+    /// Let the scripts of other origins read a response header, on top of the one server functions
+    /// use to redirect.
+    pub fn expose_header(mut self, header: HeaderName) -> Self {
+        self.exposed_headers.push(header);
+        self
+    }
+
+    /// Allow the listed origins to send cookies and other credentials.
+    ///
+    /// # Panics
+    /// Panics if every origin is allowed, since that would let any website make calls on behalf of
+    /// the visitor and read the responses.
+    #[track_caller]
+    pub fn allow_credentials(mut self) -> Self {
+        assert!(
+            self.origins != AllowedOrigins::Any,
+            "A CorsPolicy can't allow credentials from any origin, list the origins instead"
+        );
+        self.credentials = true;
+        self
+    }
+
+    /// Let browsers cache the answer to a preflight request for this long.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// The value of the `Access-Control-Allow-Origin` header for a request, if its origin is
+    /// allowed.
+    fn allow_origin_value(&self, req_head: &RequestHead) -> Option<HeaderValue> {
+        let origin = req_head.headers.get(ORIGIN)?;
+        match &self.origins {
+            AllowedOrigins::Any => Some(HeaderValue::from_static("*")),
+            AllowedOrigins::List(origins) => origins
+                .iter()
+                .any(|allowed| origin.as_bytes() == allowed.trim_end_matches('/').as_bytes())
+                .then(|| origin.clone()),
+        }
+    }
+
+    /// The CORS headers for the response to a request, if it comes from an allowed origin.
+    pub fn response_headers(&self, req_head: &RequestHead) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        // The answer depends on the origin, so caches have to keep one per origin
+        headers.insert(VARY, HeaderValue::from_static("origin"));
+        let Some(origin) = self.allow_origin_value(req_head) else {
+            return headers;
+        };
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        if self.credentials {
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+        if let Some(exposed) = join(self.exposed_headers.iter().map(HeaderName::as_str)) {
+            headers.insert(ACCESS_CONTROL_EXPOSE_HEADERS, exposed);
+        }
+        headers
+    }
+
+    /// The CORS headers for the answer to a preflight request, if it comes from an allowed origin
+    /// and asks for an allowed method.
+    pub fn preflight_headers(&self, req_head: &RequestHead) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(VARY, HeaderValue::from_static("origin"));
+        let requested_method = req_head
+            .headers
+            .get(ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|method| Method::from_bytes(method.as_bytes()).ok());
+        let method_allowed = requested_method.is_some_and(|method| self.methods.contains(&method));
+        let Some(origin) = self.allow_origin_value(req_head).filter(|_| method_allowed) else {
+            return headers;
+        };
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        if self.credentials {
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+        if let Some(methods) = join(self.methods.iter().map(Method::as_str)) {
+            headers.insert(ACCESS_CONTROL_ALLOW_METHODS, methods);
+        }
+        if let Some(allowed) = join(self.headers.iter().map(HeaderName::as_str)) {
+            headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, allowed);
+        }
+        if let Some(max_age) = self.max_age {
+            headers.insert(ACCESS_CONTROL_MAX_AGE, HeaderValue::from(max_age.as_secs()));
+        }
+        headers
+    }
+}
+
+/// Add the CORS headers to the headers of a response. `Vary` is appended to, since the response
+/// may already vary on other headers.
+pub(crate) fn add_cors_headers(headers: &mut HeaderMap, cors_headers: HeaderMap) {
+    for (name, value) in cors_headers.iter() {
+        if name == VARY {
+            headers.append(name, value.clone());
+        } else {
+            headers.insert(name, value.clone());
+        }
+    }
+}
+
+/// Answers a preflight request for a server function with a `204 No Content`. The CORS headers
+/// are left out when the origin or the method isn't allowed, which makes the browser refuse to
+/// send the request.
+#[tracing::instrument(level = "trace", fields(error), skip_all)]
+pub fn server_fn_preflight(req_head: &RequestHead, cors: &CorsPolicy) -> Response {
+    let mut res = Response::no_content();
+    add_cors_headers(res.headers_mut(), cors.preflight_headers(req_head));
+    res
+}
+
+/// Join header values with commas.
+fn join<'a>(values: impl Iterator<Item = &'a str>) -> Option<HeaderValue> {
+    let joined = values.collect::<Vec<_>>().join(", ");
+    if joined.is_empty() {
+        return None;
+    }
+    HeaderValue::from_str(&joined).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(headers: &[(&str, &str)]) -> RequestHead {
+        let mut request = http::Request::builder().uri("/api/add_todo");
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        request.body(()).unwrap().into_parts().0.into()
+    }
+
+    fn preflight(origin: &str, method: &str) -> RequestHead {
+        request(&[
+            ("origin", origin),
+            ("access-control-request-method", method),
+        ])
+    }
+
+    #[test]
+    fn listed_origins_are_allowed() {
+        let cors = CorsPolicy::new().allow_origin("https://example.com/");
+        let headers = cors.response_headers(&request(&[("origin", "https://example.com")]));
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+        assert_eq!(headers[ACCESS_CONTROL_EXPOSE_HEADERS], REDIRECT_HEADER);
+        assert_eq!(headers[VARY], "origin");
+        assert!(!headers.contains_key(ACCESS_CONTROL_ALLOW_CREDENTIALS));
+    }
+
+    #[test]
+    fn other_origins_only_get_vary() {
+        let cors = CorsPolicy::new().allow_origin("https://example.com");
+        for req_head in [
+            request(&[("origin", "https://example.com.evil.net")]),
+            request(&[("origin", "http://example.com")]),
+            request(&[]),
+        ] {
+            let headers = cors.response_headers(&req_head);
+            assert_eq!(headers.len(), 1);
+            assert_eq!(headers[VARY], "origin");
+        }
+    }
+
+    #[test]
+    fn any_origin_is_answered_with_a_wildcard() {
+        let cors = CorsPolicy::new().allow_any_origin();
+        let headers = cors.response_headers(&request(&[("origin", "https://example.com")]));
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    }
+
+    #[test]
+    fn credentials_are_allowed_for_listed_origins() {
+        let cors = CorsPolicy::new()
+            .allow_origin("https://example.com")
+            .allow_credentials();
+        let headers = cors.response_headers(&request(&[("origin", "https://example.com")]));
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+        let headers = cors.preflight_headers(&preflight("https://example.com", "POST"));
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+    }
+
+    #[test]
+    #[should_panic]
+    fn credentials_cannot_be_allowed_for_any_origin() {
+        _ = CorsPolicy::new().allow_any_origin().allow_credentials();
+    }
+
+    #[test]
+    fn preflight_lists_what_is_allowed() {
+        let cors = CorsPolicy::new()
+            .allow_origin("https://example.com")
+            .allow_methods([Method::POST])
+            .allow_header(HeaderName::from_static("x-csrf-token"))
+            .with_max_age(Duration::from_secs(600));
+        let headers = cors.preflight_headers(&preflight("https://example.com", "POST"));
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_METHODS], "POST");
+        assert_eq!(
+            headers[ACCESS_CONTROL_ALLOW_HEADERS],
+            "content-type, accept, x-csrf-token"
+        );
+        assert_eq!(headers[ACCESS_CONTROL_MAX_AGE], "600");
+    }
+
+    #[test]
+    fn preflight_for_another_method_or_origin_is_refused() {
+        let cors = CorsPolicy::new()
+            .allow_origin("https://example.com")
+            .allow_methods([Method::POST]);
+        for req_head in [
+            preflight("https://example.com", "DELETE"),
+            preflight("https://other.com", "POST"),
+            request(&[("origin", "https://example.com")]),
+        ] {
+            let headers = cors.preflight_headers(&req_head);
+            assert_eq!(headers.len(), 1);
+            assert_eq!(headers[VARY], "origin");
+        }
+    }
+
+    #[test]
+    fn vary_is_added_to_the_one_of_the_response() {
+        let cors = CorsPolicy::new().allow_origin("https://example.com");
+        let mut headers = HeaderMap::new();
+        headers.insert(VARY, HeaderValue::from_static("accept"));
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_ORIGIN,
+            HeaderValue::from_static("https://other.com"),
+        );
+        add_cors_headers(
+            &mut headers,
+            cors.response_headers(&request(&[("origin", "https://example.com")])),
+        );
+        let vary = headers.get_all(VARY).iter().collect::<Vec<_>>();
+        assert_eq!(vary, ["accept", "origin"]);
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+    }
+}
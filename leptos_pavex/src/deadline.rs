@@ -0,0 +1,106 @@
+use futures::Future;
+use leptos::prelude::{provide_context, use_context, Owner};
+use pavex::http::StatusCode;
+use std::time::Duration;
+
+/// What to do when a route hasn't finished rendering by its [`RenderDeadline`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeadlineAction {
+    /// Send what has been rendered so far, and stream the `<Suspense/>` fragments that are still
+    /// pending as they resolve, the way `SsrMode::OutOfOrder` does.
+    Stream,
+    /// Give up on the render, and respond with this status and the error page set in
+    /// `RenderOptions`. This is usually a `503 Service Unavailable` or a `504 Gateway Timeout`.
+    Respond(StatusCode),
+}
+
+/// An upper bound on how long `SsrMode::Async` and `SsrMode::InOrder` routes can spend waiting
+/// for their resources before the response is sent.
+///
+/// For `SsrMode::InOrder` routes, the deadline covers the time until the response starts. With
+/// [`DeadlineAction::Stream`], they're rendered out of order and buffered until the deadline, so
+/// that they're still sent whole if they finish in time.
+///
+/// A missed deadline is logged as a warning, and recorded as `timed_out = true` on the span the
+/// request is logged in, which is the root span entered by `pavex_tracing::logger`. `tracing` only
+/// keeps the fields declared when a span is created, so the root span has to declare it:
+/// ```rust,ignore
+/// tracing::info_span!(
+///     "HTTP request",
+///     // ...
+///     timed_out = tracing::field::Empty,
+/// )
+/// ```
+///
+/// Deadlines are enforced with tokio's timer, which comes with the `ssr` feature. Without it,
+/// routes always render to completion, however long they take.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderDeadline {
+    timeout: Duration,
+    action: DeadlineAction,
+}
+
+impl RenderDeadline {
+    /// Fall back to out of order streaming after `timeout`.
+    pub fn stream_after(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            action: DeadlineAction::Stream,
+        }
+    }
+
+    /// Respond with `status` and the error page after `timeout`.
+    pub fn respond_after(timeout: Duration, status: StatusCode) -> Self {
+        Self {
+            timeout,
+            action: DeadlineAction::Respond(status),
+        }
+    }
+
+    /// How long the route can take to render.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// What happens once the timeout has passed.
+    pub fn action(&self) -> DeadlineAction {
+        self.action
+    }
+}
+
+/// Run a future to completion, or until `duration` has passed. The timer runs on tokio, so
+/// without the `ssr` feature the future always runs to completion, as documented on
+/// [`RenderDeadline`].
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    #[cfg(feature = "ssr")]
+    return tokio::time::timeout(duration, future).await.ok();
+    #[cfg(not(feature = "ssr"))]
+    {
+        let _ = duration;
+        Some(future.await)
+    }
+}
+
+/// The span the request is logged in, kept in the Owner of the render.
+#[derive(Clone, Debug)]
+struct RequestSpan(tracing::Span);
+
+/// Keep the current span in an Owner, to record the deadlines missed while rendering in it. It
+/// has to be called before the handler enters a span of its own.
+pub(crate) fn provide_request_span(owner: &Owner) {
+    let span = tracing::Span::current();
+    owner.with(|| provide_context(RequestSpan(span)));
+}
+
+/// The span of the request being rendered, or the current span outside of `render_route`.
+pub(crate) fn request_span() -> tracing::Span {
+    use_context::<RequestSpan>()
+        .map(|RequestSpan(span)| span)
+        .unwrap_or_else(tracing::Span::current)
+}
+
+/// Record a render that missed its deadline in the span of the request.
+pub(crate) fn record_timeout(span: &tracing::Span, timeout: Duration) {
+    span.record("timed_out", true);
+    tracing::warn!(parent: span, "Rendering took longer than {timeout:?}");
+}
@@ -0,0 +1,150 @@
+use leptos::server_fn::error::ServerFnError;
+use pavex::http::StatusCode;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The functions giving the status code of custom server function errors, used when a server
+/// function returns `ServerFnError::WrappedServerError`. Custom errors without one are sent with a
+/// `500 Internal Server Error`.
+///
+/// `handle_server_fns` provides them in the Owner of the server function, where its response is
+/// built. An app with its own error type registers its constructor in `LeptosKit`:
+/// ```rust,ignore
+/// pub fn error_statuses() -> ErrorStatuses {
+///     ErrorStatuses::new().with_status(EcommerceAppError::status_code)
+/// }
+///
+/// kit.with_error_statuses(Constructor::singleton(f!(crate::error_statuses)))
+/// ```
+#[derive(Clone, Default)]
+pub struct ErrorStatuses {
+    // Each value is the `fn(&E) -> StatusCode` for the error type `E` it's keyed by
+    statuses: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl ErrorStatuses {
+    /// Send every custom error with a `500 Internal Server Error`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a function to give the status code of a custom error type, replacing the one it had.
+    pub fn with_status<E: 'static>(mut self, status: fn(&E) -> StatusCode) -> Self {
+        self.statuses.insert(TypeId::of::<E>(), Arc::new(status));
+        self
+    }
+
+    /// The status code of the response to a server function error. Errors in the arguments sent by
+    /// the client get a `400 Bad Request`, custom errors get the status their function gives, and
+    /// every other error gets a `500 Internal Server Error`.
+    pub fn status_of<E: 'static>(&self, err: &ServerFnError<E>) -> StatusCode {
+        match err {
+            ServerFnError::Args(_)
+            | ServerFnError::MissingArg(_)
+            | ServerFnError::Deserialization(_) => StatusCode::BAD_REQUEST,
+            ServerFnError::WrappedServerError(err) => self
+                .statuses
+                .get(&TypeId::of::<E>())
+                .and_then(|status| status.downcast_ref::<fn(&E) -> StatusCode>())
+                .map_or(StatusCode::INTERNAL_SERVER_ERROR, |status| status(err)),
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl std::fmt::Debug for ErrorStatuses {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ErrorStatuses")
+            .field("statuses", &self.statuses.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::PavexResponse;
+    use leptos::prelude::provide_context;
+    use leptos::server_fn::response::Res;
+    use reactive_graph::owner::Owner;
+    use std::fmt::{Display, Formatter};
+    use std::str::FromStr;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum ShopError {
+        OutOfStock,
+        Unavailable,
+    }
+
+    impl ShopError {
+        fn status_code(&self) -> StatusCode {
+            match self {
+                ShopError::OutOfStock => StatusCode::CONFLICT,
+                ShopError::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            }
+        }
+    }
+
+    impl Display for ShopError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{self:?}")
+        }
+    }
+
+    impl FromStr for ShopError {
+        type Err = ();
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "OutOfStock" => Ok(ShopError::OutOfStock),
+                "Unavailable" => Ok(ShopError::Unavailable),
+                _ => Err(()),
+            }
+        }
+    }
+
+    fn statuses() -> ErrorStatuses {
+        ErrorStatuses::new().with_status(ShopError::status_code)
+    }
+
+    #[test]
+    fn custom_errors_get_the_status_of_their_type() {
+        let err = ServerFnError::WrappedServerError(ShopError::OutOfStock);
+        assert_eq!(statuses().status_of(&err), StatusCode::CONFLICT);
+        let err = ServerFnError::WrappedServerError(ShopError::Unavailable);
+        assert_eq!(statuses().status_of(&err), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn custom_errors_without_a_status_are_internal_errors() {
+        let err = ServerFnError::WrappedServerError(ShopError::OutOfStock);
+        assert_eq!(
+            ErrorStatuses::new().status_of(&err),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[test]
+    fn argument_errors_are_bad_requests() {
+        let err = ServerFnError::<ShopError>::Args("missing quantity".to_string());
+        assert_eq!(statuses().status_of(&err), StatusCode::BAD_REQUEST);
+        let err = ServerFnError::<ShopError>::ServerError("database is down".to_string());
+        assert_eq!(
+            statuses().status_of(&err),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[test]
+    fn error_responses_use_the_statuses_in_the_owner() {
+        let err = ServerFnError::WrappedServerError(ShopError::OutOfStock);
+        let owner = Owner::new();
+        owner.with(|| provide_context(statuses()));
+        let res = owner.with(|| PavexResponse::error_response("/api/buy", &err));
+        assert_eq!(res.0.status(), StatusCode::CONFLICT);
+
+        let res = Owner::new().with(|| PavexResponse::error_response("/api/buy", &err));
+        assert_eq!(res.0.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -1,8 +1,10 @@
-use crate::{pavex_helpers::AppFunction, response::build_response};
+use crate::render_options::{RenderError, RENDER_PANIC_MARKER};
+use crate::{flush_policy::FlushPolicy, pavex_helpers::AppFunction, response::build_response};
 use futures::{stream::once, Future, Stream, StreamExt};
 use leptos_integration_utils::{BoxedFnOnce, PinnedFuture, PinnedStream};
-use leptos_meta:: ServerMetaContextOutput;
+use leptos_meta::ServerMetaContextOutput;
 use reactive_graph::owner::Sandboxed;
+use std::panic::AssertUnwindSafe;
 
 /// A Pavex specific version of the ExtendResponse trait from leptos_integration_utils,
 /// due to our need to take in a sized AppFunction instead of the typical closure
@@ -20,28 +22,30 @@ pub trait ExtendResponse: Sized {
         meta_context: ServerMetaContextOutput,
         additional_context: impl FnOnce() + Send + 'static,
         res_options: Self::ResponseOptions,
-        stream_builder: fn(
-            AppFunction,
-            BoxedFnOnce<PinnedStream<String>>,
-        ) -> PinnedFuture<PinnedStream<String>>,
+        flush_policy: FlushPolicy,
+        stream_builder: impl FnOnce(
+                AppFunction,
+                BoxedFnOnce<PinnedStream<String>>,
+            ) -> PinnedFuture<PinnedStream<String>>
+            + Send
+            + 'static,
     ) -> impl Future<Output = Self> + Send {
         async move {
             let (owner, stream) = build_response(app_fn, additional_context, stream_builder);
-            let stream = stream.await.ready_chunks(32).map(|n| n.join(""));
+            let stream = flush_policy.coalesce(stream.await);
 
             let sc = owner.shared_context().unwrap();
             while let Some(pending) = sc.await_deferred() {
                 pending.await;
             }
 
-            let mut stream =
-                Box::pin(meta_context.inject_meta_context(stream).await);
+            let mut stream = Box::pin(meta_context.inject_meta_context(stream).await);
             // wait for the first chunk of the stream, then set the status and headers
             let first_chunk = stream.next().await.unwrap_or_default();
 
             let mut res = Self::from_stream(Sandboxed::new(
                 once(async move { first_chunk })
-                    .chain(stream)
+                    .chain(end_on_panic(stream))
                     // drop the owner, cleaning up the reactive runtime,
                     // once the stream is over
                     .chain(once(async move {
@@ -60,3 +64,40 @@ pub trait ExtendResponse: Sized {
         }
     }
 }
+
+/// End a stream with [`RENDER_PANIC_MARKER`] if rendering panics, instead of dropping the
+/// connection. Once the first chunk is sent, it's too late to send the error page.
+fn end_on_panic(
+    stream: impl Stream<Item = String> + Send + 'static,
+) -> impl Stream<Item = String> + Send {
+    AssertUnwindSafe(stream).catch_unwind().map(|chunk| {
+        chunk.unwrap_or_else(|payload| {
+            let error = RenderError::from_panic(payload.as_ref());
+            tracing::error!(panic = %error.message(), "Panicked while streaming the app");
+            RENDER_PANIC_MARKER.to_string()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+
+    #[test]
+    fn panic_ends_the_stream_with_the_marker() {
+        let chunks = stream::iter(["<main>", "boom", "</main>"]).map(|chunk| {
+            assert_ne!(chunk, "boom", "the render panicked");
+            chunk.to_string()
+        });
+        let html = futures::executor::block_on(end_on_panic(chunks).collect::<Vec<_>>());
+        assert_eq!(html, ["<main>", RENDER_PANIC_MARKER]);
+    }
+
+    #[test]
+    fn stream_without_panic_is_passed_through() {
+        let chunks = stream::iter(["<main>", "</main>"]).map(str::to_string);
+        let html = futures::executor::block_on(end_on_panic(chunks).collect::<Vec<_>>());
+        assert_eq!(html, ["<main>", "</main>"]);
+    }
+}
@@ -13,16 +13,21 @@ pub struct SubPath<'a> {
 /// A Pavex handler to serve all files contained in the folder. For Leptos, all files handled by cargo-leptos
 /// will be placed in the `target/site` folder, and this folder is thus exposed.
 pub fn serve_files(subpath: &PathParams<SubPath>) -> Response {
-    let prefix = "target/site";
+    serve_site_file("target/site", subpath.0.path).unwrap_or_else(Response::not_found)
+}
 
-    let basepath = Path::new(&format!("./{}", prefix)).to_path_buf();
-    let mut path = match basepath.join(subpath.0.path).canonicalize() {
-        Ok(p) => p,
-        Err(e) => {
-            eprintln!("Path Failure: {e}");
-            return Response::not_found();
-        }
-    };
+/// Serve a file from the site root, or the `index.html` in it if the path is a folder. Returns
+/// `None` if there's no such file, or if the path leads outside of the site root.
+pub fn serve_site_file(site_root: &str, subpath: &str) -> Option<Response> {
+    let basepath = Path::new(site_root).canonicalize().ok()?;
+    // Paths that don't exist fail to canonicalize
+    let mut path = basepath
+        .join(subpath.trim_start_matches('/'))
+        .canonicalize()
+        .ok()?;
+    if !path.starts_with(&basepath) {
+        return None;
+    }
 
     if path.is_dir() {
         path.push("index.html");
@@ -30,8 +35,8 @@ pub fn serve_files(subpath: &PathParams<SubPath>) -> Response {
 
     match path.try_exists() {
         Ok(true) => {}
-        Ok(false) => return Response::not_found(),
-        Err(_) => return Response::internal_server_error(),
+        Ok(false) => return None,
+        Err(_) => return Some(Response::internal_server_error()),
     }
 
     let mime = mime_guess::from_path(&path)
@@ -40,11 +45,11 @@ pub fn serve_files(subpath: &PathParams<SubPath>) -> Response {
 
     let hv = pavex::http::HeaderValue::from_str(&mime).expect("valid mime type");
 
-    match fs::read(path) {
+    Some(match fs::read(path) {
         Ok(file) => Response::ok()
             .append_header(CONTENT_TYPE, hv)
             .append_header(CONTENT_LENGTH, file.len().into())
             .set_raw_body(Full::new(file.into())),
         Err(_) => Response::internal_server_error(),
-    }
+    })
 }
@@ -0,0 +1,229 @@
+use futures::stream::Fuse;
+use futures::{Stream, StreamExt};
+use pin_project::pin_project;
+#[cfg(feature = "ssr")]
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Decides when the chunks of HTML rendered by Leptos are written to the response.
+///
+/// Chunks that are ready at the same time are joined together and flushed as one, up to
+/// `max_chunks` chunks or `max_bytes` bytes. By default, whatever is buffered is flushed as soon
+/// as the app stops producing HTML, which gives the best time to first byte. Setting a
+/// `max_latency` holds buffered HTML back for up to that long waiting for more, which sends fewer,
+/// larger packets over slow connections.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FlushPolicy {
+    max_chunks: usize,
+    max_bytes: Option<usize>,
+    max_latency: Option<Duration>,
+}
+
+impl Default for FlushPolicy {
+    fn default() -> Self {
+        Self {
+            max_chunks: 32,
+            max_bytes: None,
+            max_latency: None,
+        }
+    }
+}
+
+impl FlushPolicy {
+    /// Create the default policy, which flushes up to 32 ready chunks at once.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Flush once this many chunks have been buffered. Values below 1 are treated as 1.
+    pub fn with_max_chunks(mut self, max_chunks: usize) -> Self {
+        self.max_chunks = max_chunks.max(1);
+        self
+    }
+
+    /// Flush once at least this many bytes have been buffered.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Hold buffered HTML back for up to this long, waiting for more of it, before flushing it.
+    /// The timer runs on tokio, so this only has an effect with the `ssr` feature enabled.
+    pub fn with_max_latency(mut self, max_latency: Duration) -> Self {
+        self.max_latency = Some(max_latency);
+        self
+    }
+
+    /// The most chunks buffered before they're flushed.
+    pub fn max_chunks(&self) -> usize {
+        self.max_chunks
+    }
+
+    /// The most bytes buffered before they're flushed, if limited.
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bytes
+    }
+
+    /// How long buffered HTML can be held back waiting for more, if at all.
+    pub fn max_latency(&self) -> Option<Duration> {
+        self.max_latency
+    }
+
+    /// Join the chunks of an HTML stream according to this policy.
+    pub fn coalesce<S>(self, stream: S) -> Coalesce<S>
+    where
+        S: Stream<Item = String>,
+    {
+        Coalesce {
+            inner: stream.fuse(),
+            policy: self,
+            buffer: String::new(),
+            buffered_chunks: 0,
+            #[cfg(feature = "ssr")]
+            deadline: None,
+        }
+    }
+
+    fn is_full(&self, buffered_chunks: usize, buffered_bytes: usize) -> bool {
+        buffered_chunks >= self.max_chunks
+            || self
+                .max_bytes
+                .is_some_and(|max_bytes| buffered_bytes >= max_bytes)
+    }
+}
+
+/// A stream of HTML chunks joined together according to a [`FlushPolicy`].
+#[pin_project]
+pub struct Coalesce<S>
+where
+    S: Stream<Item = String>,
+{
+    #[pin]
+    inner: Fuse<S>,
+    policy: FlushPolicy,
+    buffer: String,
+    buffered_chunks: usize,
+    #[cfg(feature = "ssr")]
+    deadline: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl<S> Stream for Coalesce<S>
+where
+    S: Stream<Item = String>,
+{
+    type Item = String;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            match this.inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some(chunk)) => {
+                    #[cfg(feature = "ssr")]
+                    if *this.buffered_chunks == 0 {
+                        *this.deadline = this
+                            .policy
+                            .max_latency
+                            .map(|max_latency| Box::pin(tokio::time::sleep(max_latency)));
+                    }
+                    this.buffer.push_str(&chunk);
+                    *this.buffered_chunks += 1;
+                    if this
+                        .policy
+                        .is_full(*this.buffered_chunks, this.buffer.len())
+                    {
+                        return Poll::Ready(Some(flush(this.buffer, this.buffered_chunks)));
+                    }
+                }
+                Poll::Ready(None) if *this.buffered_chunks == 0 => return Poll::Ready(None),
+                Poll::Ready(None) => {
+                    return Poll::Ready(Some(flush(this.buffer, this.buffered_chunks)))
+                }
+                Poll::Pending if *this.buffered_chunks == 0 => return Poll::Pending,
+                Poll::Pending => {
+                    // With a max latency, buffered HTML waits for more until the deadline passes
+                    #[cfg(feature = "ssr")]
+                    if let Some(deadline) = this.deadline.as_mut() {
+                        if deadline.as_mut().poll(cx).is_pending() {
+                            return Poll::Pending;
+                        }
+                    }
+                    return Poll::Ready(Some(flush(this.buffer, this.buffered_chunks)));
+                }
+            }
+        }
+    }
+}
+
+fn flush(buffer: &mut String, buffered_chunks: &mut usize) -> String {
+    *buffered_chunks = 0;
+    std::mem::take(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use std::collections::VecDeque;
+
+    /// A stream yielding the chunks it's given, and `Pending` for each `None` among them.
+    struct Scripted(VecDeque<Option<&'static str>>);
+
+    impl Stream for Scripted {
+        type Item = String;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<String>> {
+            match self.0.pop_front() {
+                Some(Some(chunk)) => Poll::Ready(Some(chunk.to_string())),
+                Some(None) => {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+                None => Poll::Ready(None),
+            }
+        }
+    }
+
+    fn coalesce(policy: FlushPolicy, script: &[Option<&'static str>]) -> Vec<String> {
+        block_on(
+            policy
+                .coalesce(Scripted(script.iter().copied().collect()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn ready_chunks_are_joined_up_to_the_chunk_limit() {
+        let policy = FlushPolicy::new().with_max_chunks(2);
+        let script = [Some("a"), Some("b"), Some("c"), Some("d"), Some("e")];
+        assert_eq!(coalesce(policy, &script), ["ab", "cd", "e"]);
+    }
+
+    #[test]
+    fn chunks_are_flushed_once_over_the_byte_budget() {
+        let policy = FlushPolicy::new().with_max_bytes(4);
+        let script = [Some("abc"), Some("de"), Some("f"), Some("ghijk"), Some("l")];
+        assert_eq!(coalesce(policy, &script), ["abcde", "fghijk", "l"]);
+    }
+
+    #[test]
+    fn buffered_chunks_are_flushed_when_the_app_stops_producing_html() {
+        let script = [
+            None,
+            Some("a"),
+            Some("b"),
+            None,
+            Some("c"),
+            None,
+            None,
+            Some("d"),
+        ];
+        assert_eq!(coalesce(FlushPolicy::new(), &script), ["ab", "c", "d"]);
+    }
+
+    #[test]
+    fn empty_streams_flush_nothing() {
+        assert!(coalesce(FlushPolicy::new(), &[None]).is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+use crate::leptos_routes::add_leptos_routes_with_groups;
+use crate::mount::MountPoint;
+use crate::origin::RequestOrigin;
+use crate::pavex_helpers::{ComponentOwner, ServerFnOwner};
+use crate::route_conflicts::ReservedRoutes;
+use crate::route_groups::RouteGroup;
+use crate::{pass_leptos_context_with_origin, PavexRouteList, RouteType};
+use http::Method;
+use pavex::blueprint::constructor::Constructor;
+use pavex::blueprint::linter::Lint;
+#[cfg(feature = "ssr")]
+use pavex::blueprint::router::GET;
+use pavex::blueprint::Blueprint;
+use pavex::request::RequestHead;
+use pavex::{f, t};
+
+/// Everything a Pavex blueprint needs to serve a Leptos app, registered in one call, in the style
+/// of Pavex's `ApiKit`:
+/// ```rust,ignore
+/// LeptosKit::new(
+///     routes::leptos_routes(),
+///     Constructor::request_scoped(f!(crate::leptos::generate_app)),
+/// )
+/// .with_component_owner(Constructor::request_scoped(f!(
+///     crate::leptos::additional_context_components
+/// )))
+/// .register(&mut bp);
+/// ```
+///
+/// It registers:
+/// - the routes generated from the Leptos router, along with the server functions,
+/// - the constructors for the app, its Owners, the route table, the render options, the CORS
+///   policy, the server function body limits, the request origin and the mount point,
+/// - `LeptosOptions` and the `Vec<PavexRouteListing>` as prebuilt types, which the server has to
+///   pass to `build_application_state`,
+/// - a `GET /*path` route serving the site root, or a fallback serving it and rendering the app's
+///   not found view, with the `ssr` feature.
+///
+/// The server functions also need a `BodySizeLimit`, which Pavex's `ApiKit` registers.
+///
+/// Every constructor can be replaced with the `with_*` methods, and every piece can be left out by
+/// setting its field to `None`, for example to register it with a different lifecycle yourself.
+#[derive(Clone, Debug)]
+pub struct LeptosKit {
+    /// The routes generated from the Leptos router.
+    pub routes: PavexRouteList,
+    /// The routes registered by hand, checked for conflicts with the generated ones.
+    pub reserved_routes: ReservedRoutes,
+    /// The groups of routes registered behind their own middleware.
+    pub route_groups: Vec<RouteGroup>,
+    /// The constructor for the `AppFunction` rendered by every route.
+    pub app_function: Option<Constructor>,
+    /// The constructor for the `ComponentOwner` routes are rendered in. By default, it provides no
+    /// additional context.
+    pub component_owner: Option<Constructor>,
+    /// The constructor for the `ServerFnOwner` server functions run in. By default, it provides no
+    /// additional context.
+    pub server_fn_owner: Option<Constructor>,
+    /// The constructor for the `PavexRouteTable`.
+    pub route_table: Option<Constructor>,
+    /// The constructor for the `RenderOptions`.
+    pub render_options: Option<Constructor>,
+    /// The constructor for the `RenderConfig` the route handlers take the route table, the
+    /// `LeptosOptions` and the `RenderOptions` from.
+    pub render_config: Option<Constructor>,
+    /// The constructor for the `TrustedProxies`.
+    pub trusted_proxies: Option<Constructor>,
+    /// The constructor for the `CorsPolicy` of the server functions.
+    pub cors_policy: Option<Constructor>,
+    /// The constructor for the `ServerFnBodyLimits`.
+    pub server_fn_body_limits: Option<Constructor>,
+    /// The constructor for the `ErrorStatuses` of custom server function errors.
+    pub error_statuses: Option<Constructor>,
+    /// The constructor for the `RequestOrigin`.
+    pub request_origin: Option<Constructor>,
+    /// The path prefix the app is mounted under, which the server functions are registered at.
+    pub mount: MountPoint,
+    /// The constructor for the `MountPoint` of each request, which has to return `mount`.
+    pub mount_point: Option<Constructor>,
+    /// Whether `LeptosOptions` and the `Vec<PavexRouteListing>` are registered as prebuilt types.
+    pub prebuilt_types: bool,
+    /// Whether the site root is served at `GET /*path`.
+    pub static_files: bool,
+    /// Whether `render_fallback` is registered as the fallback of the blueprint.
+    pub fallback: bool,
+}
+
+impl LeptosKit {
+    /// Create a kit with the default constructors, for a route list and the constructor of the
+    /// app's `AppFunction`.
+    pub fn new(routes: PavexRouteList, app_function: Constructor) -> Self {
+        Self {
+            routes,
+            reserved_routes: ReservedRoutes::new(),
+            route_groups: Vec::new(),
+            app_function: Some(app_function),
+            component_owner: Some(Constructor::request_scoped(f!(crate::kit::component_owner))),
+            server_fn_owner: Some(Constructor::request_scoped(f!(crate::kit::server_fn_owner))),
+            route_table: Some(Constructor::singleton(f!(
+                crate::route_table::PavexRouteTable::new
+            ))),
+            render_options: Some(Constructor::singleton(f!(
+                crate::render_options::RenderOptions::new
+            ))),
+            render_config: Some(Constructor::singleton(f!(
+                crate::render_config::RenderConfig::new
+            ))),
+            trusted_proxies: Some(Constructor::singleton(f!(
+                crate::origin::TrustedProxies::new
+            ))),
+            cors_policy: Some(Constructor::singleton(f!(crate::cors::CorsPolicy::new))),
+            server_fn_body_limits: Some(Constructor::singleton(f!(
+                crate::body_limit::ServerFnBodyLimits::new
+            ))),
+            error_statuses: Some(Constructor::singleton(f!(
+                crate::error_status::ErrorStatuses::new
+            ))),
+            request_origin: Some(Constructor::request_scoped(f!(
+                crate::origin::RequestOrigin::extract
+            ))),
+            mount: MountPoint::root(),
+            mount_point: Some(Constructor::singleton(f!(crate::mount::MountPoint::root))),
+            prebuilt_types: true,
+            static_files: cfg!(feature = "ssr"),
+            fallback: false,
+        }
+    }
+
+    /// Check the generated routes for conflicts with these routes registered by hand.
+    pub fn with_reserved_routes(mut self, reserved_routes: ReservedRoutes) -> Self {
+        self.reserved_routes = reserved_routes;
+        self
+    }
+
+    /// Register the routes of a group in a nested blueprint, behind the group's middleware.
+    pub fn with_route_group(mut self, group: RouteGroup) -> Self {
+        self.route_groups.push(group);
+        self
+    }
+
+    /// Use another constructor for the `ComponentOwner`, usually to provide additional context.
+    pub fn with_component_owner(mut self, constructor: Constructor) -> Self {
+        self.component_owner = Some(constructor);
+        self
+    }
+
+    /// Use another constructor for the `ServerFnOwner`, usually to provide additional context.
+    pub fn with_server_fn_owner(mut self, constructor: Constructor) -> Self {
+        self.server_fn_owner = Some(constructor);
+        self
+    }
+
+    /// Use another constructor for the `PavexRouteTable`.
+    pub fn with_route_table(mut self, constructor: Constructor) -> Self {
+        self.route_table = Some(constructor);
+        self
+    }
+
+    /// Use another constructor for the `RenderOptions`.
+    pub fn with_render_options(mut self, constructor: Constructor) -> Self {
+        self.render_options = Some(constructor);
+        self
+    }
+
+    /// Use another constructor for the `TrustedProxies`.
+    pub fn with_trusted_proxies(mut self, constructor: Constructor) -> Self {
+        self.trusted_proxies = Some(constructor);
+        self
+    }
+
+    /// Use another constructor for the `CorsPolicy`, usually to allow other origins to call the
+    /// server functions.
+    pub fn with_cors_policy(mut self, constructor: Constructor) -> Self {
+        self.cors_policy = Some(constructor);
+        self
+    }
+
+    /// Use another constructor for the `ServerFnBodyLimits`, usually to give some server functions
+    /// a limit of their own.
+    pub fn with_server_fn_body_limits(mut self, constructor: Constructor) -> Self {
+        self.server_fn_body_limits = Some(constructor);
+        self
+    }
+
+    /// Use another constructor for the `ErrorStatuses`, to send the app's own server function
+    /// errors with the status codes they stand for.
+    pub fn with_error_statuses(mut self, constructor: Constructor) -> Self {
+        self.error_statuses = Some(constructor);
+        self
+    }
+
+    /// Mount the app under a path prefix. The routes should already be mounted with
+    /// `mount_routes`, and the constructor has to return the same mount point at runtime:
+    /// ```rust,ignore
+    /// let routes = mount_routes(discover_routes(app_fn), &mount_point());
+    /// LeptosKit::new(routes, app_function)
+    ///     .with_mount(mount_point(), Constructor::singleton(f!(crate::mount_point)))
+    ///     .register(&mut bp);
+    /// ```
+    pub fn with_mount(mut self, mount: MountPoint, constructor: Constructor) -> Self {
+        self.mount = mount;
+        self.mount_point = Some(constructor);
+        self
+    }
+
+    /// Don't serve the site root at `GET /*path`.
+    pub fn without_static_files(mut self) -> Self {
+        self.static_files = false;
+        self
+    }
+
+    /// Register `render_fallback` as the fallback of the blueprint, which serves the site root and
+    /// renders the app's not found view for the requests no route matches. This replaces the
+    /// `GET /*path` route.
+    pub fn with_fallback(mut self) -> Self {
+        self.fallback = true;
+        self.static_files = false;
+        self
+    }
+
+    /// Register every piece of the kit with the blueprint.
+    ///
+    /// # Panics
+    /// Panics if the generated routes conflict with each other or with the reserved routes, or if
+    /// they aren't mounted at the kit's mount point.
+    #[track_caller]
+    pub fn register(self, bp: &mut Blueprint) -> RegisteredLeptosKit {
+        let constructors = [
+            self.app_function,
+            self.component_owner,
+            self.server_fn_owner,
+            self.route_table,
+            self.render_options,
+            self.render_config,
+            self.trusted_proxies,
+            self.cors_policy,
+            self.server_fn_body_limits,
+            self.error_statuses,
+            self.request_origin,
+            self.mount_point,
+        ];
+        // Apps without server functions or static routes leave some of them unused
+        for constructor in constructors.into_iter().flatten() {
+            constructor.ignore(Lint::Unused).register(bp);
+        }
+        if self.prebuilt_types {
+            bp.prebuilt(t!(leptos::config::LeptosOptions))
+                .clone_if_necessary();
+            bp.prebuilt(t!(std::vec::Vec<crate::PavexRouteListing>))
+                .clone_if_necessary();
+        }
+
+        let mut reserved_routes = self.reserved_routes;
+        if self.static_files {
+            reserved_routes = reserved_routes.with_route(Method::GET, "/*path");
+        }
+        add_leptos_routes_with_groups(
+            &self.routes,
+            &reserved_routes,
+            &self.mount,
+            &self.route_groups,
+            bp,
+        );
+        #[cfg(feature = "ssr")]
+        if self.static_files {
+            bp.route(GET, "/*path", f!(crate::file_helpers::serve_files));
+        }
+        #[cfg(feature = "ssr")]
+        if self.fallback {
+            bp.fallback(f!(crate::render_fallback));
+        }
+        RegisteredLeptosKit {}
+    }
+}
+
+/// The type returned by [`LeptosKit::register`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RegisteredLeptosKit {}
+
+/// Build the `ComponentOwner` for a request, without any additional context.
+pub fn component_owner(req_head: &RequestHead, origin: &RequestOrigin) -> ComponentOwner {
+    let (owner, meta_context_output) =
+        pass_leptos_context_with_origin(&RouteType::Component, req_head, origin, || {});
+    ComponentOwner::new(owner, meta_context_output)
+}
+
+/// Build the `ServerFnOwner` for a request, without any additional context.
+pub fn server_fn_owner(req_head: &RequestHead, origin: &RequestOrigin) -> ServerFnOwner {
+    let (owner, _) = pass_leptos_context_with_origin(&RouteType::ServerFn, req_head, origin, || {});
+    ServerFnOwner::new(owner)
+}
@@ -1,57 +1,227 @@
+use crate::mount::MountPoint;
+use crate::route_conflicts::{check_route_conflicts, ReservedRoutes};
+use crate::route_groups::RouteGroup;
+use crate::trailing_slash::TrailingSlash;
+use crate::{init_executor, PavexRouteList, PavexRouteListing};
 use http::Method;
-use leptos_router::Method as LeptosMethod;
 use pavex::{
     blueprint::{
-        router::{DELETE, GET, PATCH, POST, PUT},
+        reflection::RawIdentifiers,
+        router::{MethodGuard, CONNECT, DELETE, GET, HEAD, OPTIONS, PATCH, POST, PUT, TRACE},
         Blueprint,
     },
     f,
 };
-use crate::{init_executor, PavexRouteList};
+use std::collections::HashSet;
 
 /// A convenience function to add all routes defined in Leptos to the Pavex router automatically.
 /// Requires a mutable reference to the blueprint and a list of routes from Leptos
 pub fn add_leptos_routes(paths: &PavexRouteList, bp: &mut Blueprint) {
+    add_leptos_routes_with_reserved(paths, &ReservedRoutes::default(), bp);
+}
+
+/// Like [`add_leptos_routes`], but first checks that the routes from Leptos don't conflict with
+/// the routes registered by hand, so that the conflicts are reported while the blueprint is built.
+///
+/// # Panics
+/// Panics with the list of conflicts if there are any.
+pub fn add_leptos_routes_with_reserved(
+    paths: &PavexRouteList,
+    reserved: &ReservedRoutes,
+    bp: &mut Blueprint,
+) {
+    add_leptos_routes_at(paths, reserved, &MountPoint::root(), bp);
+}
+
+/// Like [`add_leptos_routes_with_reserved`], for an app mounted under a path prefix. The routes
+/// should already be mounted with `mount_routes`, and the server functions are registered under
+/// the prefix too.
+///
+/// # Panics
+/// Panics with the list of conflicts if there are any, or if a route is mounted elsewhere.
+pub fn add_leptos_routes_at(
+    paths: &PavexRouteList,
+    reserved: &ReservedRoutes,
+    mount: &MountPoint,
+    bp: &mut Blueprint,
+) {
+    add_leptos_routes_with_groups(paths, reserved, mount, &[], bp);
+}
+
+/// Like [`add_leptos_routes_at`], registering the routes that belong to a [`RouteGroup`] in a
+/// nested blueprint, behind the group's middleware.
+///
+/// # Panics
+/// Panics with the list of conflicts if there are any, or if a route is mounted elsewhere.
+pub fn add_leptos_routes_with_groups(
+    paths: &PavexRouteList,
+    reserved: &ReservedRoutes,
+    mount: &MountPoint,
+    groups: &[RouteGroup],
+    bp: &mut Blueprint,
+) {
     init_executor();
 
-    // register server functions
+    // the server functions are registered under `mount`, so the routes have to be mounted there too
+    if let Some(listing) = paths.iter().find(|listing| listing.mount() != mount) {
+        panic!(
+            "The route {} is mounted at {:?}, but the Leptos routes are registered at {:?}",
+            listing.path(),
+            listing.mount().prefix(),
+            mount.prefix()
+        );
+    }
+
+    if let Err(conflicts) = check_route_conflicts(paths, reserved, mount) {
+        panic!("{conflicts}");
+    }
+
+    // register server functions, along with the preflight requests browsers send before calling
+    // them from other origins
+    let mut preflight_paths = HashSet::new();
     for (path, method) in crate::server_fn::server_fn_paths() {
-        let method = match method {
+        let path = mount.join(path);
+        let guard = match method {
             Method::GET => GET,
             Method::POST => POST,
             Method::PUT => PUT,
             Method::DELETE => DELETE,
             Method::PATCH => PATCH,
+            Method::HEAD => HEAD,
+            Method::CONNECT => CONNECT,
+            Method::TRACE => TRACE,
             _ => {
-                panic!(
-                    "Unsupported server function HTTP method: \
-                 {method:?}"
-                );
+                tracing::warn!("Skipping the server function at {path}: {method} can't be routed");
+                continue;
             }
         };
-        bp.route(
-            
-            method,
-            path,
-            f!(crate::server_fn::handle_server_fns),
-        );
+        bp.route(guard, &path, f!(crate::server_fn::handle_server_fns));
+        if preflight_paths.insert(path.clone()) {
+            bp.route(OPTIONS, &path, f!(crate::cors::server_fn_preflight));
+        }
     }
 
-    // register router paths
+    // register router paths, each in the blueprint of the first group it belongs to
+    let mut group_bps = groups.iter().map(RouteGroup::blueprint).collect::<Vec<_>>();
     for listing in paths.iter() {
-        let path = listing.path();
-        for method in listing.methods() {
-            bp.route(
-                match method {
-                    LeptosMethod::Get => GET,
-                    LeptosMethod::Post => POST,
-                    LeptosMethod::Put => PUT,
-                    LeptosMethod::Delete => DELETE,
-                    LeptosMethod::Patch => PATCH,
-                },
-                path,
-                f!(crate::render_route),
-            );
+        match groups.iter().position(|group| group.contains(listing)) {
+            Some(index) => register_route(&mut group_bps[index], listing),
+            None => register_route(bp, listing),
         }
     }
+    for group_bp in group_bps {
+        bp.nest(group_bp);
+    }
+}
+
+/// Register the path of a listing, along with the path that only differs by a trailing slash
+fn register_route(bp: &mut Blueprint, listing: &PavexRouteListing) {
+    for (method, path, handler) in listing_routes(listing) {
+        bp.route(MethodGuard::from_iter([method]), &path, handler);
+    }
+}
+
+/// The handler for each method at each path a listing is registered at: its own path, along with
+/// HEAD and OPTIONS, and the path that only differs by a trailing slash unless it's strict
+fn listing_routes(listing: &PavexRouteListing) -> Vec<(Method, String, RawIdentifiers)> {
+    let mut routes = Vec::new();
+    for method in listing.allowed_methods() {
+        // HEAD renders like GET without sending the body, OPTIONS lists the allowed methods
+        let handler = match method {
+            Method::HEAD => f!(crate::render_route_head),
+            Method::OPTIONS => f!(crate::route_options),
+            _ => f!(crate::render_route),
+        };
+        match (listing.trailing_slash(), listing.alternate_path()) {
+            // The route table only knows the path of the listing, so every method redirects to it,
+            // HEAD and OPTIONS included
+            (TrailingSlash::Redirect, Some(alternate)) => routes.push((
+                method.clone(),
+                alternate,
+                f!(crate::trailing_slash::redirect_to_canonical),
+            )),
+            (TrailingSlash::Both, Some(alternate)) => {
+                routes.push((method.clone(), alternate, handler.clone()))
+            }
+            _ => {}
+        }
+        routes.push((method, listing.path().to_string(), handler));
+    }
+    routes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use leptos_router::{Method as LeptosMethod, SsrMode};
+
+    fn handlers_at(listing: &PavexRouteListing, path: &str) -> Vec<(Method, &'static str)> {
+        listing_routes(listing)
+            .into_iter()
+            .filter(|(_, route_path, _)| route_path == path)
+            .map(|(method, _, handler)| (method, handler.import_path))
+            .collect()
+    }
+
+    fn listing(trailing_slash: TrailingSlash) -> PavexRouteListing {
+        PavexRouteListing::new(
+            "/about".to_string(),
+            SsrMode::Async,
+            [LeptosMethod::Get],
+            None,
+        )
+        .with_trailing_slash(trailing_slash)
+    }
+
+    #[test]
+    fn strict_routes_are_only_registered_at_their_path() {
+        let listing = listing(TrailingSlash::Strict);
+        assert_eq!(
+            handlers_at(&listing, "/about"),
+            [
+                (Method::GET, "crate::render_route"),
+                (Method::HEAD, "crate::render_route_head"),
+                (Method::OPTIONS, "crate::route_options"),
+            ]
+        );
+        assert!(handlers_at(&listing, "/about/").is_empty());
+    }
+
+    #[test]
+    fn redirect_alternate_redirects_every_method() {
+        let listing = listing(TrailingSlash::Redirect);
+        assert_eq!(
+            handlers_at(&listing, "/about/"),
+            [
+                (Method::GET, "crate::trailing_slash::redirect_to_canonical"),
+                (Method::HEAD, "crate::trailing_slash::redirect_to_canonical"),
+                (
+                    Method::OPTIONS,
+                    "crate::trailing_slash::redirect_to_canonical"
+                ),
+            ]
+        );
+        assert_eq!(handlers_at(&listing, "/about").len(), 3);
+    }
+
+    #[test]
+    fn both_alternate_renders_like_the_route() {
+        let listing = listing(TrailingSlash::Both);
+        assert_eq!(
+            handlers_at(&listing, "/about/"),
+            handlers_at(&listing, "/about")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "is mounted at \"/shop\"")]
+    fn routes_mounted_elsewhere_are_refused() {
+        let routes = vec![listing(TrailingSlash::Strict).with_mount(MountPoint::new("/shop"))];
+        add_leptos_routes_at(
+            &routes,
+            &ReservedRoutes::default(),
+            &MountPoint::new("/app"),
+            &mut Blueprint::new(),
+        );
+    }
 }
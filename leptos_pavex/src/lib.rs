@@ -1,51 +1,86 @@
+pub mod body_limit;
+pub mod cache;
+pub mod cors;
+pub mod deadline;
+pub mod error_status;
 #[allow(dead_code)]
 pub mod extend_response;
 #[cfg(feature = "ssr")]
 pub mod file_helpers;
+pub mod flush_policy;
+pub mod kit;
 
 pub mod leptos_routes;
+pub mod manifest;
+pub mod mount;
+pub mod origin;
 pub mod pavex_helpers;
+pub mod render_config;
+pub mod render_options;
+pub mod replace_blocks;
 pub mod request;
 pub mod request_parts;
 pub mod response;
 pub mod response_options;
+pub mod route_conflicts;
+pub mod route_groups;
+pub mod route_overrides;
+pub mod route_table;
 pub mod server_fn;
+pub mod static_routes;
 pub mod stream;
+pub mod trailing_slash;
 
 use bytes::Bytes;
+use deadline::{DeadlineAction, RenderDeadline};
 use extend_response::ExtendResponse;
 use futures::stream::once;
-use futures::{Stream, StreamExt};
+use futures::{FutureExt, Stream, StreamExt};
 use hydration_context::SsrSharedContext;
 use leptos::server_fn::redirect::REDIRECT_HEADER;
 use reactive_graph::owner::expect_context;
 use std::io;
+use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::mount::MountPoint;
+use crate::origin::RequestOrigin;
 use crate::request_parts::RequestParts;
 use crate::response_options::ResponseOptions;
 use leptos::prelude::{provide_context, use_context, Owner};
 use leptos::tachys::view::RenderHtml;
+use leptos::IntoView;
 use leptos_integration_utils::{BoxedFnOnce, PinnedFuture, PinnedStream};
 use leptos_meta::{ServerMetaContext, ServerMetaContextOutput};
 use leptos_router::components::provide_server_redirect;
 use leptos_router::location::RequestUrl;
 use leptos_router::{PathSegment, RouteList, RouteListing, SsrMode, StaticDataMap, StaticMode};
-use pavex::http::header::{ACCEPT, LOCATION};
+use pavex::http::header::{ACCEPT, ALLOW, LOCATION};
 use pavex::http::uri::PathAndQuery;
-use pavex::http::StatusCode;
 use pavex::http::{HeaderName, HeaderValue};
+use pavex::http::{Method, StatusCode};
 use pavex::request::body::RawIncomingBody;
 use pavex::request::path::MatchedPathPattern;
 use pavex::request::RequestHead;
 use pavex::response::Response;
-use pavex_helpers::{AppFunction, ComponentOwner};
+#[cfg(feature = "ssr")]
+use pavex::router::{default_fallback, AllowedMethods};
+use pavex_helpers::{generate_app_function, stub_request_head, AppFunction, ComponentOwner};
+#[cfg(feature = "ssr")]
+use percent_encoding::percent_decode_str;
 use reactive_graph::computed::ScopedFuture;
+use render_config::RenderConfig;
+use render_options::{RenderError, RenderOptions};
 use response::PavexResponse;
+use route_table::PavexRouteTable;
+use static_routes::StaticPage;
+use trailing_slash::TrailingSlash;
 
 /// Provides an easy way to redirect the user from within a server function. Mimicking the Remix `redirect()`,
-/// it sets a StatusCode of 302 and a LOCATION header with the provided value.
+/// it sets a LOCATION header with the provided value, along with a 303 or 302 status for plain form
+/// requests, or the REDIRECT_HEADER for the server fn client.
 /// If looking to redirect from the client, `leptos_router::use_navigate()` should be used instead
 pub fn redirect(path: &str) {
     if let (Some(req), Some(res)) = (
@@ -66,8 +101,8 @@ pub fn redirect(path: &str) {
             .unwrap_or(false);
         if accepts_html {
             // if the request accepts text/html, it's a plain form request and needs
-            // to have the 302 code set
-            res.set_status(StatusCode::FOUND);
+            // to have a real redirect status set
+            res.set_status(response::form_redirect_status(Some(req.method())));
         } else {
             // otherwise, we sent it from the server fn client and actually don't want
             // to set a real redirect, as this will break the ability to return data
@@ -118,9 +153,17 @@ pub async fn render_app_to_stream(
     req_body: RawIncomingBody,
     app_fn: AppFunction,
     meta_context_output: ServerMetaContextOutput,
+    render_options: &RenderOptions,
 ) -> Response {
-    render_app_to_stream_and_replace_blocks(req_head, req_body, app_fn, meta_context_output, false)
-        .await
+    render_app_to_stream_and_replace_blocks(
+        req_head,
+        req_body,
+        app_fn,
+        meta_context_output,
+        render_options,
+        false,
+    )
+    .await
 }
 
 /// Returns a Pavex Response containing an HTML stream of your application.
@@ -130,34 +173,251 @@ pub async fn render_app_to_stream(
 ///
 /// This is a handy entrypoint for a Pavex handler, taking in both additional context
 /// and the rendering mode of each route in your Leptos app.
-#[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub async fn render_route(
-    paths: PavexRouteList,
+    config: &RenderConfig,
+    req_head: RequestHead,
+    req_body: RawIncomingBody,
+    matched_path: &MatchedPathPattern,
+    context: ComponentOwner,
+    app_fn: AppFunction,
+) -> Response {
+    // Missed deadlines are recorded on the span the request is logged in, which is still the
+    // current one until the render enters its own
+    deadline::provide_request_span(context.owner());
+    render_listing(config, req_head, req_body, matched_path, context, app_fn).await
+}
+
+/// Render the route matched by a request, the way `render_route` does
+#[tracing::instrument(name = "render_route", level = "trace", fields(error), skip_all)]
+async fn render_listing(
+    config: &RenderConfig,
     req_head: RequestHead,
     req_body: RawIncomingBody,
     matched_path: &MatchedPathPattern,
     mut context: ComponentOwner,
     app_fn: AppFunction,
 ) -> Response {
-    // 1. Process route to match the values in routeListing
-    let path = &matched_path.to_string();
-    // 2. Find RouteListing in paths. This should probably be optimized, we probably don't want to
-    // search for this every time
-    let listing: &PavexRouteListing = paths
+    let (routes, options, render_options) =
+        (config.routes(), config.options(), config.render_options());
+    // 1. Find the RouteListing for the matched path in the prebuilt route table
+    let Some(listing) = routes.get(matched_path.inner()) else {
+        tracing::error!(
+            "Failed to find the route {matched_path} requested by the user. \
+             This suggests that the routing rules in the Router that \
+             call this handler needs to be edited!"
+        );
+        return routes.miss_policy().response();
+    };
+    // 2. Serve the response from the cache, if it's there
+    let cache_key = render_options.cache_key(&req_head);
+    if let Some(res) = cache_key
+        .as_ref()
+        .and_then(|key| render_options.cached_response(key))
+    {
+        return res;
+    }
+    // 3. Serve statically rendered routes from the site root if they've already been generated.
+    // The pages written there are rendered with a stub request, so that they don't depend on the
+    // visitor's, and only for the paths the route is generated for
+    let mode = listing.mode();
+    let is_static = listing.static_mode().is_some() && req_head.method == Method::GET;
+    let request_path = req_head.target.path().to_string();
+    let stale_page = if is_static {
+        match static_routes::static_page(options, &request_path, routes.static_revalidation()) {
+            StaticPage::Fresh(res) => return res,
+            StaticPage::Stale(res) => Some(res),
+            StaticPage::Missing => None,
+        }
+    } else {
+        None
+    };
+    let is_persisted = is_static && static_routes::persists_path(&request_path);
+    if is_persisted {
+        // Stale pages are served as they are, while the fresh version is rendered in the
+        // background
+        if let Some(stale_page) = stale_page {
+            static_routes::regenerate_page(options, &request_path, listing.mount());
+            return stale_page;
+        }
+        let page =
+            static_routes::render_persisted_page(options, &request_path, listing.mount()).await;
+        if let Some(res) = page {
+            return res;
+        }
+    }
+    // 4. Match listing mode against known, and choose function
+    let deadline = render_options.render_deadline(listing.path());
+    let meta_output = context.take_meta_context_output();
+    let owner = context.owner().clone();
+    if render_options.fallback_not_found() {
+        preset_fallback_status(routes, &owner);
+    }
+
+    let request = AppRequest {
+        req_head,
+        req_body,
+        app_fn,
+        meta_output,
+    };
+    let res = render_mode(mode, deadline, &owner, request, render_options).await;
+    match cache_key {
+        Some(key) => render_options.cache_response(key, res).await,
+        None => res,
+    }
+}
+
+/// Set a `404 Not Found` status before rendering if the Leptos router is going to render its
+/// fallback, because none of its routes match the `RequestUrl` in the Owner. The status is set on
+/// the ResponseOptions of the request, so the app can still override it.
+pub fn preset_fallback_status(routes: &PavexRouteTable, owner: &Owner) {
+    owner.with(|| {
+        let Some(url) = use_context::<RequestUrl>() else {
+            return;
+        };
+        if routes.router_matches(url.as_ref()) {
+            return;
+        }
+        let res_options = ResponseOptions::default();
+        res_options.set_status(StatusCode::NOT_FOUND);
+        provide_context(res_options);
+    });
+}
+
+/// Answers a HEAD request for a route with the status and headers `render_route` would send for a
+/// GET, without the body.
+///
+/// The route is rendered as far as it takes to produce the response head, which for streamed
+/// routes is the first chunk of HTML, and the rest of the render is dropped along with the body.
+pub async fn render_route_head(
+    config: &RenderConfig,
+    mut req_head: RequestHead,
+    req_body: RawIncomingBody,
+    matched_path: &MatchedPathPattern,
+    context: ComponentOwner,
+    app_fn: AppFunction,
+) -> Response {
+    // Rendered as a GET, so that cached and statically rendered pages are used too
+    req_head.method = Method::GET;
+    let res = render_route(config, req_head, req_body, matched_path, context, app_fn).await;
+
+    let mut head = Response::new(res.status());
+    head.headers_mut().extend(res.headers().clone());
+    head
+}
+
+/// Answers an OPTIONS request for a route with a `204 No Content` listing the methods it allows
+/// in the `Allow` header.
+#[tracing::instrument(level = "trace", fields(error), skip_all)]
+pub fn route_options(routes: &PavexRouteTable, matched_path: &MatchedPathPattern) -> Response {
+    let Some(listing) = routes.get(matched_path.inner()) else {
+        return routes.miss_policy().response();
+    };
+    let allowed = listing
+        .allowed_methods()
         .iter()
-        .find(|r| r.path() == matched_path.inner())
-        .unwrap_or_else(|| {
-            panic!(
-                "Failed to find the route {path} requested by the user. \
-                    This suggests that the routing rules in the Router that \
-                    call this handler needs to be edited!"
-            )
-        });
-    // 3. Match listing mode against known, and choose function
+        .map(Method::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+    Response::no_content().insert_header(
+        ALLOW,
+        HeaderValue::from_str(&allowed).expect("Failed to create HeaderValue"),
+    )
+}
+
+/// A Pavex fallback for the requests no route matches. GET and HEAD requests are first served
+/// from the files in the site root, and otherwise get the Leptos app rendered with a
+/// `404 Not Found`, so that the router's own fallback view is sent with the app's layout, meta
+/// tags and hydration scripts:
+/// ```rust,ignore
+/// bp.fallback(f!(leptos_pavex::render_fallback));
+/// ```
+/// Paths with routes for other methods get a `405 Method Not Allowed` listing them, like they do
+/// with Pavex's default fallback, and other methods get a bare `404 Not Found`. Like the route
+/// handlers, it takes the [`RenderConfig`] singleton `LeptosKit` registers.
+#[cfg(feature = "ssr")]
+#[tracing::instrument(level = "trace", fields(error), skip_all)]
+pub async fn render_fallback(
+    config: &RenderConfig,
+    req_head: RequestHead,
+    req_body: RawIncomingBody,
+    allowed_methods: &AllowedMethods,
+    mut context: ComponentOwner,
+    app_fn: AppFunction,
+) -> Response {
+    let res = default_fallback(allowed_methods).await;
+    if res.status() != StatusCode::NOT_FOUND {
+        return res;
+    }
+    let is_head = req_head.method == Method::HEAD;
+    if req_head.method != Method::GET && !is_head {
+        return res;
+    }
+    // Files are looked up by their name, not by the percent-encoded path
+    let path = percent_decode_str(req_head.target.path()).decode_utf8();
+    if let Some(res) = path
+        .ok()
+        .and_then(|path| file_helpers::serve_site_file(&config.options().site_root, &path))
+    {
+        return res;
+    }
+
     let meta_output = context.take_meta_context_output();
-    let owner = context.owner();
+    let owner = context.owner().clone();
+    // The app can still set another status through its ResponseOptions
+    owner.with(|| {
+        let res_options = ResponseOptions::default();
+        res_options.set_status(StatusCode::NOT_FOUND);
+        provide_context(res_options);
+    });
+    let request = AppRequest {
+        req_head,
+        req_body,
+        app_fn,
+        meta_output,
+    };
+    let res = render_mode(
+        SsrMode::default(),
+        None,
+        &owner,
+        request,
+        config.render_options(),
+    )
+    .await;
 
-    match listing.mode() {
+    if is_head {
+        let mut head = Response::new(res.status());
+        head.headers_mut().extend(res.headers().clone());
+        return head;
+    }
+    res
+}
+
+/// The request a route renders the app for, along with the app and its meta context
+struct AppRequest {
+    req_head: RequestHead,
+    req_body: RawIncomingBody,
+    app_fn: AppFunction,
+    meta_output: ServerMetaContextOutput,
+}
+
+/// Render the app with the given rendering mode, inside of its root Owner
+async fn render_mode(
+    mode: SsrMode,
+    deadline: Option<RenderDeadline>,
+    owner: &Owner,
+    request: AppRequest,
+    render_options: &RenderOptions,
+) -> Response {
+    if let (SsrMode::InOrder | SsrMode::Async, Some(deadline)) = (&mode, deadline) {
+        return render_mode_with_deadline(mode, deadline, owner, request, render_options).await;
+    }
+    let AppRequest {
+        req_head,
+        req_body,
+        app_fn,
+        meta_output,
+    } = request;
+    match mode {
         SsrMode::OutOfOrder => {
             owner
                 .with(|| {
@@ -166,6 +426,7 @@ pub async fn render_route(
                         req_body,
                         app_fn,
                         meta_output,
+                        render_options,
                     ))
                 })
                 .await
@@ -178,6 +439,7 @@ pub async fn render_route(
                         req_body,
                         app_fn,
                         meta_output,
+                        render_options,
                         true,
                     ))
                 })
@@ -191,6 +453,7 @@ pub async fn render_route(
                         req_body,
                         app_fn,
                         meta_output,
+                        render_options,
                     ))
                 })
                 .await
@@ -198,7 +461,13 @@ pub async fn render_route(
         SsrMode::Async => {
             owner
                 .with(|| {
-                    ScopedFuture::new(render_app_async(req_head, req_body, app_fn, meta_output))
+                    ScopedFuture::new(render_app_async(
+                        req_head,
+                        req_body,
+                        app_fn,
+                        meta_output,
+                        render_options,
+                    ))
                 })
                 .await
         }
@@ -213,31 +482,64 @@ pub async fn render_route(
 /// The HTML stream is rendered using leptos's render_to_stream and includes everything
 /// defined in the documentation for that function.
 ///
-/// For now, this is identical to render_app_to_stream()
+/// `replace_blocks` additionally lets you specify whether `<Suspense/>` fragments that read
+/// from blocking resources should be retrojected into the HTML that's initially served, rather
+/// than dynamically inserting them with JavaScript on the client. This means you will have
+/// better support if JavaScript is not enabled, in exchange for a marginally slower response time.
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub async fn render_app_to_stream_and_replace_blocks(
     req_head: RequestHead,
-    req_body: RawIncomingBody,
+    _req_body: RawIncomingBody,
     app_fn: AppFunction,
     meta_context_output: ServerMetaContextOutput,
+    render_options: &RenderOptions,
     replace_blocks: bool,
 ) -> Response {
-    _ = replace_blocks; // TODO
+    if !replace_blocks {
+        return handle_response(
+            req_head,
+            app_fn,
+            meta_context_output,
+            render_options,
+            |app, chunks| {
+                Box::pin(async move {
+                    Box::pin(app.inner().to_html_stream_out_of_order().chain(chunks()))
+                        as PinnedStream<String>
+                })
+            },
+        )
+        .await;
+    }
     handle_response(
         req_head,
-        req_body,
         app_fn,
         meta_context_output,
-        |app, chunks| {
-            Box::pin(async move {
-                Box::pin(app.inner().to_html_stream_out_of_order().chain(chunks()))
-                    as PinnedStream<String>
-            })
-        },
+        render_options,
+        replace_blocks_stream_builder,
     )
     .await
 }
 
+/// Builds the HTML stream for `SsrMode::PartiallyBlocked`, with the fragments of the blocking
+/// resources spliced into the shell
+fn replace_blocks_stream_builder(
+    app: AppFunction,
+    chunks: BoxedFnOnce<PinnedStream<String>>,
+) -> PinnedFuture<PinnedStream<String>> {
+    let shared_context = Owner::current_shared_context();
+    Box::pin(async move {
+        let app = Box::pin(app.inner().to_html_stream_out_of_order());
+        // Rendering the shell registers the blocking resources, so once they have all
+        // resolved their fragments are ready to be pulled out of the stream
+        if let Some(shared_context) = shared_context {
+            while let Some(pending) = shared_context.await_deferred() {
+                pending.await;
+            }
+        }
+        Box::pin(replace_blocks::replace_blocks(app).await.chain(chunks())) as PinnedStream<String>
+    })
+}
+
 /// Returns a Pavex Response containing an HTML stream of your application.
 ///
 /// It provides a MetaContext and a RouterIntegrationContext to the app's context
@@ -248,15 +550,16 @@ pub async fn render_app_to_stream_and_replace_blocks(
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub async fn render_app_to_stream_in_order(
     req_head: RequestHead,
-    req_body: RawIncomingBody,
+    _req_body: RawIncomingBody,
     app_fn: AppFunction,
     meta_context_output: ServerMetaContextOutput,
+    render_options: &RenderOptions,
 ) -> Response {
     handle_response(
         req_head,
-        req_body,
         app_fn,
         meta_context_output,
+        render_options,
         |app, chunks| {
             Box::pin(async move {
                 Box::pin(app.inner().to_html_stream_in_order().chain(chunks()))
@@ -277,41 +580,167 @@ pub async fn render_app_to_stream_in_order(
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub async fn render_app_async(
     req_head: RequestHead,
-    req_body: RawIncomingBody,
+    _req_body: RawIncomingBody,
     app_fn: AppFunction,
     meta_context_output: ServerMetaContextOutput,
+    render_options: &RenderOptions,
 ) -> Response {
     handle_response(
         req_head,
-        req_body,
         app_fn,
         meta_context_output,
-        |app, chunks| {
+        render_options,
+        async_stream_builder,
+    )
+    .await
+}
+
+/// Builds the HTML stream for `SsrMode::Async`, waiting for the whole app to render before
+/// returning it as a single chunk
+fn async_stream_builder(
+    app: AppFunction,
+    chunks: BoxedFnOnce<PinnedStream<String>>,
+) -> PinnedFuture<PinnedStream<String>> {
+    Box::pin(async move {
+        let app = app
+            .inner()
+            .to_html_stream_in_order()
+            .collect::<String>()
+            .await;
+        let chunks = chunks();
+        Box::pin(once(async move { app }).chain(chunks)) as PinnedStream<String>
+    })
+}
+/// Returns a Pavex Response containing the HTML of your application, sent as a single chunk if
+/// it finishes rendering within `timeout`, and streamed out of order otherwise.
+///
+/// The app is rendered out of order and buffered. If every `<Suspense/>` fragment resolves before
+/// the timeout, they're spliced into place and the page is sent whole, like `SsrMode::Async`.
+/// Otherwise, what has been rendered so far is sent, and the remaining fragments are streamed as
+/// they resolve, like `SsrMode::OutOfOrder`. The timeout is recorded in the `timed_out` field of
+/// the span the request is logged in, see [`RenderDeadline`].
+pub async fn render_app_to_stream_with_deadline(
+    req_head: RequestHead,
+    _req_body: RawIncomingBody,
+    app_fn: AppFunction,
+    meta_context_output: ServerMetaContextOutput,
+    render_options: &RenderOptions,
+    timeout: Duration,
+) -> Response {
+    let span = deadline::request_span();
+    handle_response(
+        req_head,
+        app_fn,
+        meta_context_output,
+        render_options,
+        move |app, chunks| {
             Box::pin(async move {
-                let app = app
-                    .inner()
-                    .to_html_stream_in_order()
-                    .collect::<String>()
-                    .await;
-                let chunks = chunks();
-                Box::pin(once(async move { app }).chain(chunks)) as PinnedStream<String>
+                let mut app = Box::pin(app.inner().to_html_stream_out_of_order());
+                let mut html = String::new();
+                let rendered = deadline::timeout(timeout, async {
+                    while let Some(chunk) = app.next().await {
+                        html.push_str(&chunk);
+                    }
+                })
+                .await;
+
+                if rendered.is_some() {
+                    let html = replace_blocks::splice_fragments(&html);
+                    return Box::pin(once(async move { html }).chain(chunks()))
+                        as PinnedStream<String>;
+                }
+                deadline::record_timeout(&span, timeout);
+                Box::pin(once(async move { html }).chain(app).chain(chunks()))
+                    as PinnedStream<String>
             })
         },
     )
     .await
 }
+
+/// Render an `SsrMode::InOrder` or `SsrMode::Async` route within its deadline, inside of its root
+/// Owner
+async fn render_mode_with_deadline(
+    mode: SsrMode,
+    deadline: RenderDeadline,
+    owner: &Owner,
+    request: AppRequest,
+    render_options: &RenderOptions,
+) -> Response {
+    let AppRequest {
+        req_head,
+        req_body,
+        app_fn,
+        meta_output,
+    } = request;
+    let status = match deadline.action() {
+        DeadlineAction::Stream => {
+            return owner
+                .with(|| {
+                    ScopedFuture::new(render_app_to_stream_with_deadline(
+                        req_head,
+                        req_body,
+                        app_fn,
+                        meta_output,
+                        render_options,
+                        deadline.timeout(),
+                    ))
+                })
+                .await;
+        }
+        DeadlineAction::Respond(status) => status,
+    };
+
+    let res = match mode {
+        SsrMode::InOrder => {
+            deadline::timeout(
+                deadline.timeout(),
+                owner.with(|| {
+                    ScopedFuture::new(render_app_to_stream_in_order(
+                        req_head,
+                        req_body,
+                        app_fn,
+                        meta_output,
+                        render_options,
+                    ))
+                }),
+            )
+            .await
+        }
+        _ => {
+            deadline::timeout(
+                deadline.timeout(),
+                owner.with(|| {
+                    ScopedFuture::new(render_app_async(
+                        req_head,
+                        req_body,
+                        app_fn,
+                        meta_output,
+                        render_options,
+                    ))
+                }),
+            )
+            .await
+        }
+    };
+    res.unwrap_or_else(|| {
+        deadline::record_timeout(&owner.with(deadline::request_span), deadline.timeout());
+        render_options.error_response(&RenderError::timeout(deadline.timeout(), status))
+    })
+}
+
 /// A convenience function leptos_pavex uses to build the Pavex Response in a variety of ways
 async fn handle_response(
     req_head: RequestHead,
-    _req_body: RawIncomingBody,
     app_fn: AppFunction,
     meta_context_output: ServerMetaContextOutput,
-    stream_builder: fn(
-        AppFunction,
-        BoxedFnOnce<PinnedStream<String>>,
-    ) -> PinnedFuture<PinnedStream<String>>,
+    render_options: &RenderOptions,
+    stream_builder: impl FnOnce(AppFunction, BoxedFnOnce<PinnedStream<String>>) -> PinnedFuture<PinnedStream<String>>
+        + Send
+        + 'static,
 ) -> Response {
-    let res_options: ResponseOptions = ResponseOptions::default();
+    // The ResponseOptions may have been provided ahead of time to set a default status
+    let res_options = use_context::<ResponseOptions>().unwrap_or_default();
     let meta_context = expect_context::<ServerMetaContext>();
 
     let additional_context = {
@@ -322,22 +751,36 @@ async fn handle_response(
             // For reasons that escape me, if the incoming URI protocol is https, it provides the absolute URI
             let path = req_head.target.path_and_query().unwrap().as_str();
 
-            let full_path = format!("http://leptos.dev{path}");
-            let req_parts = RequestParts::new_from_req(&req_head);
+            // The origin is provided by pass_leptos_context, falling back to the one in the request
+            let origin = use_context::<RequestOrigin>()
+                .unwrap_or_else(|| RequestOrigin::untrusted(&req_head));
+            let full_path = origin.url(path);
+            let req_parts = RequestParts::new_from_req(&req_head).with_origin(&origin);
             provide_post_contexts(&full_path, meta_context, req_parts, res_options.clone());
         }
     };
 
-    let res = PavexResponse::from_app(
+    // A panic while rendering would otherwise take the connection down with it, so it's turned
+    // into a 500 response instead. Panics after the first chunk end the body with a marker
+    let res = AssertUnwindSafe(PavexResponse::from_app(
         app_fn,
         meta_context_output,
         additional_context,
         res_options,
+        render_options.flush_policy(),
         stream_builder,
-    )
+    ))
+    .catch_unwind()
     .await;
 
-    res.0
+    match res {
+        Ok(res) => res.0,
+        Err(payload) => {
+            let error = RenderError::from_panic(payload.as_ref());
+            tracing::error!(panic = %error.message(), "Panicked while rendering the app");
+            render_options.error_response(&error)
+        }
+    }
 }
 
 /// Provide additional information to Leptos from an outside environment. This could be global
@@ -345,6 +788,7 @@ async fn handle_response(
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub fn provide_initial_contexts(
     req_head: &RequestHead,
+    origin: RequestOrigin,
     parts: RequestParts,
     meta_context: ServerMetaContext,
 ) {
@@ -353,7 +797,13 @@ pub fn provide_initial_contexts(
         .path_and_query()
         .cloned()
         .unwrap_or(PathAndQuery::from_static("/"));
-    provide_context(RequestUrl::new(&path.to_string()));
+    // The Leptos router matches paths in the app, without the prefix it's mounted under
+    let path = origin
+        .mount()
+        .strip(path.as_str())
+        .unwrap_or_else(|| path.to_string());
+    provide_context(RequestUrl::new(&origin.url(&path)));
+    provide_context(origin);
     provide_context(parts);
     provide_context(meta_context);
 
@@ -405,7 +855,8 @@ pub fn generate_route_list_with_ssg(app_fn: AppFunction) -> (PavexRouteList, Sta
 /// Generates a list of all routes defined in Leptos's Router in your app. We can then use this to automatically
 /// create routes in Axum's Router without having to use wildcard matching or fallbacks. Takes in your root app Element
 /// as an argument, so it can walk you app tree. This version is tailored to generate Axum compatible paths. Adding excluded_routes
-/// to this function will stop `.leptos_routes()` from generating a route for it, allowing a custom handler. These need to be in Axum path format
+/// to this function will stop `.leptos_routes()` from generating a route for it, allowing a custom handler. These are Pavex paths,
+/// and can be glob patterns like `/admin/**`, see `RouteOverrides`
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub fn generate_route_list_with_exclusions(
     app_fn: AppFunction,
@@ -417,7 +868,8 @@ pub fn generate_route_list_with_exclusions(
 /// Generates a list of all routes defined in Leptos's Router in your app. We can then use this to automatically
 /// create routes in Axum's Router without having to use wildcard matching or fallbacks. Takes in your root app Element
 /// as an argument, so it can walk you app tree. This version is tailored to generate Axum compatible paths. Adding excluded_routes
-/// to this function will stop `.leptos_routes()` from generating a route for it, allowing a custom handler. These need to be in Axum path format
+/// to this function will stop `.leptos_routes()` from generating a route for it, allowing a custom handler. These are Pavex paths,
+/// and can be glob patterns like `/admin/**`, see `RouteOverrides`
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub fn generate_route_list_with_exclusions_and_ssg(
     app_fn: AppFunction,
@@ -426,6 +878,41 @@ pub fn generate_route_list_with_exclusions_and_ssg(
     generate_route_list_with_exclusions_and_ssg_and_context(app_fn, excluded_routes, || {})
 }
 
+/// Discovers every route defined in Leptos's Router in your app, without needing a request.
+///
+/// Takes the same app shell closure as `build_static_routes`, and sets up the Owner, meta context
+/// and a stub request to render it with internally:
+/// ```rust,ignore
+/// let routes = discover_routes(move || shell(leptos_options.clone()));
+/// add_leptos_routes(&routes, bp);
+/// ```
+#[tracing::instrument(level = "trace", fields(error), skip_all)]
+pub fn discover_routes<IV>(app_fn: impl Fn() -> IV + Clone + Send + 'static) -> PavexRouteList
+where
+    IV: IntoView + 'static,
+{
+    discover_routes_with_context(app_fn, || {})
+}
+
+/// Like [`discover_routes`], but with additional context provided to the app while it's walked,
+/// for apps that expect some of their context to be there when they're rendered.
+#[tracing::instrument(level = "trace", fields(error), skip_all)]
+pub fn discover_routes_with_context<IV>(
+    app_fn: impl Fn() -> IV + Clone + Send + 'static,
+    additional_context: impl Fn() + 'static + Clone,
+) -> PavexRouteList
+where
+    IV: IntoView + 'static,
+{
+    init_executor();
+
+    let req_head = stub_request_head("/");
+    let (owner, _) =
+        pass_leptos_context(&RouteType::Component, &req_head, additional_context.clone());
+    let app = owner.with(|| generate_app_function(app_fn));
+    generate_route_list_with_exclusions_and_ssg_and_context(app, None, additional_context).0
+}
+
 /// A convenience type for a collection of Pavex routes
 pub type PavexRouteList = Vec<PavexRouteListing>;
 
@@ -436,15 +923,15 @@ pub struct PavexRouteListing {
     mode: SsrMode,
     methods: Vec<leptos_router::Method>,
     static_mode: Option<(StaticMode, StaticDataMap)>,
+    trailing_slash: TrailingSlash,
+    mount: MountPoint,
 }
 
 impl From<RouteListing> for PavexRouteListing {
     fn from(value: RouteListing) -> Self {
-        let path = value.path().to_pavex_path();
-        let path = if path.is_empty() {
-            "/".to_string()
-        } else {
-            path
+        let path = match value.path().to_pavex_path() {
+            path if path.is_empty() => "/".to_string(),
+            path => path,
         };
         let mode = value.mode();
         let methods = value.methods().collect();
@@ -454,6 +941,8 @@ impl From<RouteListing> for PavexRouteListing {
             mode,
             methods,
             static_mode,
+            trailing_slash: TrailingSlash::default(),
+            mount: MountPoint::root(),
         }
     }
 }
@@ -471,14 +960,64 @@ impl PavexRouteListing {
             mode,
             methods: methods.into_iter().collect(),
             static_mode,
+            trailing_slash: TrailingSlash::default(),
+            mount: MountPoint::root(),
         }
     }
 
+    /// Render this route with another `SsrMode`.
+    pub fn with_mode(mut self, mode: SsrMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Register this route for other HTTP methods.
+    pub fn with_methods(
+        mut self,
+        methods: impl IntoIterator<Item = leptos_router::Method>,
+    ) -> Self {
+        self.methods = methods.into_iter().collect();
+        self
+    }
+
+    /// Set how the path that only differs from this route's by a trailing slash is handled.
+    pub fn with_trailing_slash(mut self, trailing_slash: TrailingSlash) -> Self {
+        self.trailing_slash = trailing_slash;
+        self
+    }
+
+    /// Serve this route under a path prefix, prepending it to its path.
+    pub fn with_mount(mut self, mount: MountPoint) -> Self {
+        self.path = mount.join(&self.path);
+        self.mount = mount;
+        self
+    }
+
     /// The path this route handles.
     pub fn path(&self) -> &str {
         &self.path
     }
 
+    /// The path prefix this route is served under.
+    pub fn mount(&self) -> &MountPoint {
+        &self.mount
+    }
+
+    /// How the path that only differs from this route's by a trailing slash is handled.
+    pub fn trailing_slash(&self) -> TrailingSlash {
+        self.trailing_slash
+    }
+
+    /// The path that only differs from this route's by a trailing slash, if it's registered too.
+    pub fn alternate_path(&self) -> Option<String> {
+        match self.trailing_slash {
+            TrailingSlash::Strict => None,
+            TrailingSlash::Redirect | TrailingSlash::Both => {
+                trailing_slash::alternate_path(&self.path)
+            }
+        }
+    }
+
     /// The rendering mode for this path.
     pub fn mode(&self) -> SsrMode {
         self.mode
@@ -489,6 +1028,26 @@ impl PavexRouteListing {
         self.methods.iter().copied()
     }
 
+    /// The HTTP methods the route answers to. This adds HEAD for routes that answer GET, and
+    /// OPTIONS for every route.
+    pub fn allowed_methods(&self) -> Vec<Method> {
+        let mut methods = self
+            .methods()
+            .map(|method| match method {
+                leptos_router::Method::Get => Method::GET,
+                leptos_router::Method::Post => Method::POST,
+                leptos_router::Method::Put => Method::PUT,
+                leptos_router::Method::Delete => Method::DELETE,
+                leptos_router::Method::Patch => Method::PATCH,
+            })
+            .collect::<Vec<_>>();
+        if methods.contains(&Method::GET) {
+            methods.push(Method::HEAD);
+        }
+        methods.push(Method::OPTIONS);
+        methods
+    }
+
     /// Whether this route is statically rendered.
     #[inline(always)]
     pub fn static_mode(&self) -> Option<StaticMode> {
@@ -503,8 +1062,9 @@ trait PavexPath {
 impl PavexPath for &[PathSegment] {
     fn to_pavex_path(&self) -> String {
         let mut path = String::new();
+        // Trailing slashes are kept as they're written in the router, see `TrailingSlash` for how
+        // the path without them is handled
         for segment in self.iter() {
-            // TODO trailing slash handling
             let raw = segment.as_raw_str();
             if !raw.is_empty() && !raw.starts_with('/') {
                 path.push('/');
@@ -529,7 +1089,8 @@ impl PavexPath for &[PathSegment] {
 /// Generates a list of all routes defined in Leptos's Router in your app. We can then use this to automatically
 /// create routes in Axum's Router without having to use wildcard matching or fallbacks. Take in your root app Element
 /// as an argument, so it can walk your app tree. This version is tailored to generate Axum compatible paths. Adding excluded_routes
-/// to this function will stop `.leptos_routes()` from generating a route for it, allowing a custom handler. These need to be in Axum path format
+/// to this function will stop `.leptos_routes()` from generating a route for it, allowing a custom handler. These are Pavex paths,
+/// and can be glob patterns like `/admin/**`, see `RouteOverrides`
 /// Additional context will be provided to the app Element.
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub fn generate_route_list_with_exclusions_and_ssg_and_context(
@@ -548,8 +1109,7 @@ pub fn generate_route_list_with_exclusions_and_ssg_and_context(
             let (meta_context, _) = ServerMetaContext::new();
             provide_post_contexts("", meta_context, mock_parts, Default::default());
             additional_context();
-            let foo = RouteList::generate(move || app_fn.inner());
-            foo
+            RouteList::generate(move || app_fn.inner())
         })
         .unwrap();
 
@@ -571,12 +1131,13 @@ pub fn generate_route_list_with_exclusions_and_ssg_and_context(
         } else {
             // Routes to exclude from auto generation
             if let Some(excluded_routes) = excluded_routes {
-                routes.retain(|p| !excluded_routes.iter().any(|e| e == p.path()))
+                routes.retain(|p| !route_overrides::is_excluded(&excluded_routes, p.path()))
             }
             routes
         },
-        StaticDataMap::new(), // TODO
-                              //static_data_map,
+        // leptos_router doesn't carry the static data of its routes yet, the params of static
+        // routes are given to `static_routes::build_static_routes` instead
+        StaticDataMap::new(),
     )
 }
 
@@ -593,19 +1154,148 @@ pub fn pass_leptos_context(
     route_type: &RouteType,
     req_head: &RequestHead,
     additional_context: impl Fn() + 'static + Clone,
+) -> (Owner, ServerMetaContextOutput) {
+    pass_leptos_context_with_origin(
+        route_type,
+        req_head,
+        &RequestOrigin::untrusted(req_head),
+        additional_context,
+    )
+}
+
+/// Like [`pass_leptos_context`], but with the origin the client used, so that `RequestUrl` and
+/// `RequestParts` carry the real scheme and host when the server runs behind a proxy. The origin
+/// usually comes from the [`RequestOrigin::extract`] constructor.
+pub fn pass_leptos_context_with_origin(
+    route_type: &RouteType,
+    req_head: &RequestHead,
+    origin: &RequestOrigin,
+    additional_context: impl Fn() + 'static + Clone,
 ) -> (Owner, ServerMetaContextOutput) {
     let owner = match route_type {
         RouteType::ServerFn => Owner::new(),
         RouteType::Component => Owner::new_root(Some(Arc::new(SsrSharedContext::new()))),
     };
-    let req_parts = RequestParts::new_from_req(&req_head);
+    let req_parts = RequestParts::new_from_req(req_head).with_origin(origin);
 
     let (meta_context, meta_context_output) = ServerMetaContext::new();
     // Set the created Owner as the current one, by setting the thread local. Pavex pins each request to their own
     // thread, so this should be fineTM
     owner.with(|| {
-        provide_initial_contexts(req_head, req_parts, meta_context);
+        provide_initial_contexts(req_head, origin.clone(), req_parts, meta_context);
         additional_context();
     });
     (owner, meta_context_output)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+    use leptos::prelude::{ElementChild, Get, Resource, Suspense};
+    use leptos::view;
+    use leptos_router::components::{Route, Router, Routes};
+    use leptos_router::{ParamSegment, StaticSegment};
+
+    fn app() -> impl IntoView {
+        view! {
+            <Router>
+                <Routes fallback=|| view! { <p>"Not found"</p> }>
+                    <Route
+                        path=(StaticSegment("products"), ParamSegment("id"))
+                        view=|| view! { <p>"Product"</p> }
+                    />
+                </Routes>
+            </Router>
+        }
+    }
+
+    fn routes() -> PavexRouteTable {
+        PavexRouteTable::new(&vec![PavexRouteListing::new(
+            "/products/:id".to_string(),
+            SsrMode::Async,
+            [leptos_router::Method::Get],
+            None,
+        )])
+    }
+
+    /// Render the app for a path the way `render_route` renders an `SsrMode::Async` route, which
+    /// can't be called without the body of a real connection
+    fn render(routes: &PavexRouteTable, path: &str) -> (StatusCode, String) {
+        render_with(
+            app,
+            path,
+            |owner| preset_fallback_status(routes, owner),
+            async_stream_builder,
+        )
+    }
+
+    fn render_with<IV: IntoView + 'static>(
+        app: fn() -> IV,
+        path: &str,
+        before_render: impl FnOnce(&Owner),
+        stream_builder: fn(
+            AppFunction,
+            BoxedFnOnce<PinnedStream<String>>,
+        ) -> PinnedFuture<PinnedStream<String>>,
+    ) -> (StatusCode, String) {
+        let _ = any_spawner::Executor::init_futures_executor();
+        let req_head = stub_request_head(path);
+        let origin = RequestOrigin::untrusted(&req_head);
+        let (owner, meta_output) =
+            pass_leptos_context_with_origin(&RouteType::Component, &req_head, &origin, || {});
+        before_render(&owner);
+        let app_fn = owner.with(|| generate_app_function(app));
+        let render_options = RenderOptions::default();
+        futures::executor::block_on(async {
+            let res = owner
+                .with(|| {
+                    ScopedFuture::new(handle_response(
+                        req_head,
+                        app_fn,
+                        meta_output,
+                        &render_options,
+                        stream_builder,
+                    ))
+                })
+                .await;
+            let status = res.status();
+            let body = res.into_parts().1.collect().await.unwrap().to_bytes();
+            (status, String::from_utf8(body.to_vec()).unwrap())
+        })
+    }
+
+    #[test]
+    fn router_fallback_is_sent_with_404() {
+        let (status, html) = render(&routes(), "/missing");
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(html.contains("Not found"));
+    }
+
+    #[test]
+    fn router_route_is_sent_with_200() {
+        let (status, html) = render(&routes(), "/products/1");
+        assert_eq!(status, StatusCode::OK);
+        assert!(html.contains("Product"));
+    }
+
+    fn blocking_app() -> impl IntoView {
+        let data = Resource::new_blocking(|| (), |_| async { "Loaded".to_string() });
+        view! {
+            <main>
+                <Suspense fallback=|| view! { <p>"Loading..."</p> }>
+                    {move || data.get().map(|data| view! { <p>{data}</p> })}
+                </Suspense>
+            </main>
+        }
+    }
+
+    #[test]
+    fn blocking_fragments_are_spliced_into_the_shell() {
+        let (status, html) = render_with(blocking_app, "/", |_| {}, replace_blocks_stream_builder);
+        assert_eq!(status, StatusCode::OK);
+        assert!(html.contains("<main><p>Loaded</p></main>"), "{html}");
+        assert!(!html.contains("Loading..."), "{html}");
+        assert!(!html.contains("<template"), "{html}");
+    }
+}
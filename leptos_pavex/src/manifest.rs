@@ -0,0 +1,184 @@
+use crate::mount::MountPoint;
+use crate::trailing_slash::TrailingSlash;
+use crate::{PavexRouteList, PavexRouteListing};
+use leptos_router::{SsrMode, StaticMode};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A description of every route and server function the app serves, meant to be written to a
+/// JSON file for deploy tooling, CDN configuration and code review:
+/// ```rust,ignore
+/// RouteManifest::new(&routes, &MountPoint::root()).write(generated_dir.join("leptos_routes.json"))?;
+/// ```
+/// Entries are sorted, so that the file only changes when the routes do.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteManifest {
+    pub routes: Vec<RouteEntry>,
+    pub server_fns: Vec<ServerFnEntry>,
+}
+
+/// A route generated from the Leptos router.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteEntry {
+    /// The Pavex path of the route.
+    pub path: String,
+    /// How the route is rendered: `out_of_order`, `partially_blocked`, `in_order` or `async`.
+    pub mode: String,
+    /// The HTTP methods the route answers to.
+    pub methods: Vec<String>,
+    /// How the route is statically rendered, `upfront` or `incremental`, if it is.
+    pub static_mode: Option<String>,
+    /// How the path with or without a trailing slash is handled: `strict`, `redirect` or `both`.
+    pub trailing_slash: String,
+}
+
+/// A registered server function.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ServerFnEntry {
+    pub path: String,
+    pub method: String,
+}
+
+impl RouteManifest {
+    /// Describe a route list, along with every server function registered in the binary, at the
+    /// paths they're registered at under the mount point. The routes should already be mounted
+    /// with `mount_routes`, like the ones passed to the blueprint.
+    pub fn new(routes: &PavexRouteList, mount: &MountPoint) -> Self {
+        let mut routes = routes.iter().map(RouteEntry::from).collect::<Vec<_>>();
+        routes.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut server_fns = crate::server_fn::server_fn_paths()
+            .map(|(path, method)| ServerFnEntry {
+                path: mount.join(path),
+                method: method.to_string(),
+            })
+            .collect::<Vec<_>>();
+        server_fns.sort();
+        Self { routes, server_fns }
+    }
+
+    /// Serialize the manifest to pretty printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Failed to serialize the route manifest")
+    }
+
+    /// Write the manifest to a JSON file.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), io::Error> {
+        fs::write(path, self.file_contents())
+    }
+
+    /// Whether the JSON file at `path` is the one `write` would write, to check in CI that the
+    /// committed manifest is up to date. A missing file isn't.
+    pub fn is_written_at(&self, path: impl AsRef<Path>) -> bool {
+        fs::read_to_string(path).is_ok_and(|contents| contents == self.file_contents())
+    }
+
+    fn file_contents(&self) -> String {
+        self.to_json() + "\n"
+    }
+}
+
+impl From<&PavexRouteListing> for RouteEntry {
+    fn from(listing: &PavexRouteListing) -> Self {
+        let mode = match listing.mode() {
+            SsrMode::OutOfOrder => "out_of_order",
+            SsrMode::PartiallyBlocked => "partially_blocked",
+            SsrMode::InOrder => "in_order",
+            SsrMode::Async => "async",
+        };
+        let static_mode = listing.static_mode().map(|static_mode| match static_mode {
+            StaticMode::Upfront => "upfront".to_string(),
+            StaticMode::Incremental => "incremental".to_string(),
+        });
+        let trailing_slash = match listing.trailing_slash() {
+            TrailingSlash::Strict => "strict",
+            TrailingSlash::Redirect => "redirect",
+            TrailingSlash::Both => "both",
+        };
+        Self {
+            path: listing.path().to_string(),
+            mode: mode.to_string(),
+            methods: listing
+                .allowed_methods()
+                .iter()
+                .map(|method| method.to_string())
+                .collect(),
+            static_mode,
+            trailing_slash: trailing_slash.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use leptos_router::{Method, StaticDataMap};
+
+    fn routes() -> PavexRouteList {
+        vec![
+            PavexRouteListing::new(
+                "/posts/:slug".to_string(),
+                SsrMode::Async,
+                [Method::Get],
+                Some((StaticMode::Incremental, StaticDataMap::new())),
+            ),
+            PavexRouteListing::new(
+                "/contact".to_string(),
+                SsrMode::OutOfOrder,
+                [Method::Get, Method::Post],
+                None,
+            )
+            .with_trailing_slash(TrailingSlash::Strict),
+        ]
+    }
+
+    fn manifest_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("leptos_pavex_manifest_{name}.json"));
+        _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn routes_are_serialized_sorted_by_path() {
+        let manifest = RouteManifest::new(&routes(), &MountPoint::root());
+        let json = serde_json::from_str::<serde_json::Value>(&manifest.to_json()).unwrap();
+        assert_eq!(
+            json["routes"][0],
+            serde_json::json!({
+                "path": "/contact",
+                "mode": "out_of_order",
+                "methods": ["GET", "POST", "HEAD", "OPTIONS"],
+                "static_mode": null,
+                "trailing_slash": "strict",
+            })
+        );
+        assert_eq!(json["routes"][1]["path"], "/posts/:slug");
+        assert_eq!(json["routes"][1]["mode"], "async");
+        assert_eq!(json["routes"][1]["static_mode"], "incremental");
+        assert_eq!(
+            serde_json::from_str::<RouteManifest>(&manifest.to_json()).unwrap(),
+            manifest
+        );
+    }
+
+    #[test]
+    fn written_manifests_are_up_to_date() {
+        let path = manifest_path("up_to_date");
+        let manifest = RouteManifest::new(&routes(), &MountPoint::root());
+        assert!(!manifest.is_written_at(&path));
+        manifest.write(&path).unwrap();
+        assert!(manifest.is_written_at(&path));
+    }
+
+    #[test]
+    fn manifests_of_other_routes_are_out_of_date() {
+        let path = manifest_path("out_of_date");
+        RouteManifest::new(&routes(), &MountPoint::root())
+            .write(&path)
+            .unwrap();
+        let mut routes = routes();
+        routes.pop();
+        assert!(!RouteManifest::new(&routes, &MountPoint::root()).is_written_at(&path));
+    }
+}
@@ -0,0 +1,134 @@
+use crate::PavexRouteList;
+
+/// The path prefix a Leptos app is served under, like `/app`, when it shares the Pavex router with
+/// other routes. The routes are written in the Leptos router without the prefix: they are
+/// registered under it, and the prefix is stripped from the `RequestUrl` the router matches
+/// against.
+///
+/// The routes registered in the blueprint are mounted when it's built, while the request handlers
+/// get the mount point from the blueprint's `MountPoint` singleton to strip it from each URL. A
+/// route list mounted at another prefix than the singleton's would be registered at paths the
+/// router can't match:
+/// ```rust,ignore
+/// pub fn mount_point() -> MountPoint {
+///     MountPoint::new("/app")
+/// }
+///
+/// let routes = mount_routes(discover_routes(app_fn), &mount_point());
+/// bp.singleton(f!(crate::mount_point));
+/// ```
+/// Server functions are registered under the prefix too, so the client has to call them there,
+/// with `leptos::server_fn::client::set_server_url("/app")` before hydrating.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MountPoint {
+    prefix: String,
+}
+
+impl MountPoint {
+    /// Mount the app under a path prefix. Leading and trailing slashes are optional.
+    pub fn new(prefix: impl AsRef<str>) -> Self {
+        let prefix = prefix.as_ref().trim_matches('/');
+        Self {
+            prefix: if prefix.is_empty() {
+                String::new()
+            } else {
+                format!("/{prefix}")
+            },
+        }
+    }
+
+    /// Mount the app at the root of the server, which is the default.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// The path prefix, without a trailing slash. It's empty for the root.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// The path on the server for a path in the app.
+    pub fn join(&self, path: &str) -> String {
+        match path {
+            _ if self.prefix.is_empty() => path.to_string(),
+            "" | "/" => self.prefix.clone(),
+            path => format!("{}{path}", self.prefix),
+        }
+    }
+
+    /// The path in the app for a path and query on the server, or `None` if it isn't under the
+    /// mount point.
+    pub fn strip(&self, path_and_query: &str) -> Option<String> {
+        let rest = path_and_query.strip_prefix(&self.prefix)?;
+        match rest.chars().next() {
+            None => Some("/".to_string()),
+            Some('?') => Some(format!("/{rest}")),
+            Some('/') => Some(rest.to_string()),
+            Some(_) => None,
+        }
+    }
+}
+
+/// Mount every route in a list under a path prefix, before it's registered with
+/// `add_leptos_routes`. It should only be applied once.
+pub fn mount_routes(routes: PavexRouteList, mount: &MountPoint) -> PavexRouteList {
+    routes
+        .into_iter()
+        .map(|listing| listing.with_mount(mount.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_are_normalized() {
+        assert_eq!(MountPoint::new("app").prefix(), "/app");
+        assert_eq!(MountPoint::new("/app/").prefix(), "/app");
+        assert_eq!(MountPoint::new("/").prefix(), "");
+        assert_eq!(MountPoint::new(""), MountPoint::root());
+    }
+
+    #[test]
+    fn paths_are_joined_under_the_prefix() {
+        let mount = MountPoint::new("/app");
+        assert_eq!(mount.join("/"), "/app");
+        assert_eq!(mount.join(""), "/app");
+        assert_eq!(mount.join("/products/:id"), "/app/products/:id");
+        assert_eq!(MountPoint::root().join("/"), "/");
+        assert_eq!(MountPoint::root().join("/about"), "/about");
+    }
+
+    #[test]
+    fn prefix_is_stripped_from_paths_under_it() {
+        let mount = MountPoint::new("/app");
+        assert_eq!(mount.strip("/app").as_deref(), Some("/"));
+        assert_eq!(mount.strip("/app/").as_deref(), Some("/"));
+        assert_eq!(mount.strip("/app?page=2").as_deref(), Some("/?page=2"));
+        assert_eq!(
+            mount.strip("/app/products/1?page=2").as_deref(),
+            Some("/products/1?page=2")
+        );
+        assert_eq!(
+            MountPoint::root().strip("/about").as_deref(),
+            Some("/about")
+        );
+    }
+
+    #[test]
+    fn paths_outside_the_prefix_are_not_stripped() {
+        let mount = MountPoint::new("/app");
+        assert_eq!(mount.strip("/application"), None);
+        assert_eq!(mount.strip("/about"), None);
+        assert_eq!(mount.strip("/"), None);
+    }
+
+    #[test]
+    fn stripping_undoes_joining() {
+        let mount = MountPoint::new("/app");
+        for path in ["/", "/about", "/products/:id"] {
+            assert_eq!(mount.strip(&mount.join(path)).as_deref(), Some(path));
+        }
+    }
+}
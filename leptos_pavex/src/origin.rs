@@ -0,0 +1,341 @@
+use crate::mount::MountPoint;
+use pavex::connection::ConnectionInfo;
+use pavex::http::uri::{Authority, Scheme};
+use pavex::http::{HeaderMap, HeaderName};
+use pavex::request::RequestHead;
+use std::net::IpAddr;
+
+const X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
+const X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
+
+/// The proxies whose `Forwarded` and `X-Forwarded-*` headers are trusted to describe the origin
+/// the client used. Requests from any other peer are described by their own `Host` header, since
+/// the forwarding headers can be set by anyone.
+///
+/// There are no trusted proxies by default, so an app behind a load balancer sees the address the
+/// balancer was reached at, like `http://internal:3000`. List the balancer's address for the URLs
+/// built during the render to use the origin the visitor typed:
+/// ```rust,ignore
+/// pub fn trusted_proxies() -> TrustedProxies {
+///     TrustedProxies::new().with_proxy("10.0.0.1".parse().unwrap())
+/// }
+///
+/// kit.with_trusted_proxies(Constructor::singleton(f!(crate::trusted_proxies)))
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TrustedProxies {
+    proxies: Vec<IpAddr>,
+}
+
+impl TrustedProxies {
+    /// Trust no proxies.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust the forwarding headers set by the proxy at this address.
+    pub fn with_proxy(mut self, proxy: IpAddr) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Whether the forwarding headers of a request from this peer can be trusted.
+    pub fn is_trusted(&self, peer: &IpAddr) -> bool {
+        self.proxies.contains(peer)
+    }
+}
+
+/// The scheme and host the client used to make a request, which may differ from the ones the
+/// server sees when it runs behind a proxy, along with the path prefix the app is mounted under.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestOrigin {
+    scheme: Scheme,
+    host: Option<Authority>,
+    mount: MountPoint,
+}
+
+impl RequestOrigin {
+    /// Work out the origin of a request, honoring its forwarding headers if the peer that sent it
+    /// is a trusted proxy.
+    ///
+    /// This is meant to be registered as a request scoped constructor in your blueprint, along with
+    /// a [`TrustedProxies`] singleton and a [`MountPoint`] one:
+    /// ```rust,ignore
+    /// bp.singleton(f!(leptos_pavex::origin::TrustedProxies::new));
+    /// bp.singleton(f!(leptos_pavex::mount::MountPoint::root));
+    /// bp.request_scoped(f!(leptos_pavex::origin::RequestOrigin::extract));
+    /// ```
+    pub fn extract(
+        req_head: &RequestHead,
+        connection_info: &ConnectionInfo,
+        trusted_proxies: &TrustedProxies,
+        mount: &MountPoint,
+    ) -> Self {
+        Self::from_peer(req_head, &connection_info.peer_addr().ip(), trusted_proxies)
+            .with_mount(mount.clone())
+    }
+
+    /// The origin of a request sent by a peer, honoring its forwarding headers if the peer is a
+    /// trusted proxy.
+    pub fn from_peer(
+        req_head: &RequestHead,
+        peer: &IpAddr,
+        trusted_proxies: &TrustedProxies,
+    ) -> Self {
+        let origin = Self::untrusted(req_head);
+        if trusted_proxies.is_trusted(peer) {
+            origin.forwarded(&req_head.headers, trusted_proxies)
+        } else {
+            origin
+        }
+    }
+
+    /// The origin of a request from its target and `Host` header alone, ignoring any forwarding
+    /// headers.
+    pub fn untrusted(req_head: &RequestHead) -> Self {
+        let host = req_head.target.authority().cloned().or_else(|| {
+            req_head
+                .headers
+                .get(pavex::http::header::HOST)
+                .and_then(|host| host.to_str().ok())
+                .and_then(|host| host.parse().ok())
+        });
+        Self {
+            scheme: req_head.target.scheme().cloned().unwrap_or(Scheme::HTTP),
+            host,
+            mount: MountPoint::root(),
+        }
+    }
+
+    /// Set the path prefix the app is mounted under.
+    pub fn with_mount(mut self, mount: MountPoint) -> Self {
+        self.mount = mount;
+        self
+    }
+
+    /// The scheme the client used.
+    pub fn scheme(&self) -> &Scheme {
+        &self.scheme
+    }
+
+    /// The host the client sent the request to, if it's known.
+    pub fn host(&self) -> Option<&Authority> {
+        self.host.as_ref()
+    }
+
+    /// The path prefix the app is mounted under.
+    pub fn mount(&self) -> &MountPoint {
+        &self.mount
+    }
+
+    /// Build the absolute URL for a path and query on this origin. Without a known host, the path
+    /// and query are returned as they are.
+    pub fn url(&self, path_and_query: &str) -> String {
+        match &self.host {
+            Some(host) => format!("{}://{host}{path_and_query}", self.scheme),
+            None => path_and_query.to_string(),
+        }
+    }
+
+    /// Override the scheme and host with the ones in the forwarding headers. The standard
+    /// `Forwarded` header wins over the `X-Forwarded-*` ones.
+    ///
+    /// Proxies append to these headers, so only their last entries were added by the trusted peer,
+    /// and anything before them may have been sent by the client. The `Forwarded` header names the
+    /// hop each entry was received from, so its entries are trusted from the right for as long as
+    /// that hop is a trusted proxy too. The `X-Forwarded-*` headers don't, so only their last entry
+    /// is used.
+    fn forwarded(mut self, headers: &HeaderMap, trusted_proxies: &TrustedProxies) -> Self {
+        let (mut proto, mut host) = (None, None);
+        let elements = list_values(headers, &pavex::http::header::FORWARDED);
+        if let Some(element) = trusted_element(&elements, trusted_proxies) {
+            for (key, value) in forwarded_pairs(element) {
+                match key.as_str() {
+                    "proto" => proto = Some(value),
+                    "host" => host = Some(value),
+                    _ => {}
+                }
+            }
+        }
+        let proto = proto.or_else(|| list_values(headers, &X_FORWARDED_PROTO).pop());
+        let host = host.or_else(|| list_values(headers, &X_FORWARDED_HOST).pop());
+
+        if let Some(scheme) = proto.and_then(|proto| proto.parse().ok()) {
+            self.scheme = scheme;
+        }
+        if let Some(host) = host.and_then(|host| host.parse().ok()) {
+            self.host = Some(host);
+        }
+        self
+    }
+}
+
+/// Every entry of a comma separated header, across all of its lines, in the order they were
+/// added.
+fn list_values(headers: &HeaderMap, name: &HeaderName) -> Vec<String> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// The leftmost `Forwarded` element that was added by a trusted proxy. The last element was added
+/// by the peer, and each element before it was added by the hop named in the `for` parameter of
+/// the element after it.
+fn trusted_element<'a>(
+    elements: &'a [String],
+    trusted_proxies: &TrustedProxies,
+) -> Option<&'a str> {
+    let mut index = elements.len().checked_sub(1)?;
+    while index > 0
+        && forwarded_for(&elements[index]).is_some_and(|ip| trusted_proxies.is_trusted(&ip))
+    {
+        index -= 1;
+    }
+    Some(&elements[index])
+}
+
+/// The `key=value` pairs of a `Forwarded` element, with lowercase keys and unquoted values.
+fn forwarded_pairs(element: &str) -> impl Iterator<Item = (String, String)> + '_ {
+    element.split(';').filter_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        Some((
+            key.trim().to_ascii_lowercase(),
+            value.trim().trim_matches('"').to_string(),
+        ))
+    })
+}
+
+/// The address in the `for` parameter of a `Forwarded` element, if it's an IP address rather than
+/// an obfuscated identifier. It may carry a port, and IPv6 addresses are in brackets.
+fn forwarded_for(element: &str) -> Option<IpAddr> {
+    let (_, node) = forwarded_pairs(element).find(|(key, _)| key == "for")?;
+    if let Some(bracketed) = node.strip_prefix('[') {
+        return bracketed.split(']').next()?.parse().ok();
+    }
+    match node.split_once(':') {
+        Some((ip, port)) if !port.contains(':') => ip.parse().ok(),
+        _ => node.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROXY: &str = "10.0.0.1";
+
+    fn request(headers: &[(&str, &str)]) -> RequestHead {
+        let mut request = http::Request::builder()
+            .uri("/")
+            .header("host", "internal:3000");
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        request.body(()).unwrap().into_parts().0.into()
+    }
+
+    fn origin(peer: &str, headers: &[(&str, &str)]) -> RequestOrigin {
+        let trusted = TrustedProxies::new()
+            .with_proxy(PROXY.parse().unwrap())
+            .with_proxy("10.0.0.2".parse().unwrap());
+        RequestOrigin::from_peer(&request(headers), &peer.parse().unwrap(), &trusted)
+    }
+
+    #[test]
+    fn untrusted_peer_ignores_forwarding_headers() {
+        let origin = origin(
+            "203.0.113.7",
+            &[
+                ("forwarded", "proto=https;host=example.com"),
+                ("x-forwarded-host", "example.com"),
+            ],
+        );
+        assert_eq!(origin.url("/a"), "http://internal:3000/a");
+    }
+
+    #[test]
+    fn trusted_peer_uses_forwarded() {
+        let origin = origin(
+            PROXY,
+            &[("forwarded", "for=203.0.113.7;proto=https;host=example.com")],
+        );
+        assert_eq!(origin.url("/a"), "https://example.com/a");
+    }
+
+    #[test]
+    fn trusted_peer_uses_x_forwarded() {
+        let origin = origin(
+            PROXY,
+            &[
+                ("x-forwarded-proto", "https"),
+                ("x-forwarded-host", "example.com"),
+            ],
+        );
+        assert_eq!(origin.url("/a"), "https://example.com/a");
+    }
+
+    #[test]
+    fn spoofed_forwarded_entries_are_ignored() {
+        let origin = origin(
+            PROXY,
+            &[(
+                "forwarded",
+                "proto=http;host=evil.com, for=203.0.113.7;proto=https;host=example.com",
+            )],
+        );
+        assert_eq!(origin.url("/a"), "https://example.com/a");
+    }
+
+    #[test]
+    fn spoofed_x_forwarded_entries_are_ignored() {
+        let origin = origin(
+            PROXY,
+            &[
+                ("x-forwarded-proto", "http, https"),
+                ("x-forwarded-host", "evil.com, example.com"),
+            ],
+        );
+        assert_eq!(origin.url("/a"), "https://example.com/a");
+    }
+
+    #[test]
+    fn spoofed_entries_on_another_header_line_are_ignored() {
+        let origin = origin(
+            PROXY,
+            &[
+                ("x-forwarded-host", "evil.com"),
+                ("x-forwarded-host", "example.com"),
+            ],
+        );
+        assert_eq!(origin.url("/a"), "http://example.com/a");
+    }
+
+    #[test]
+    fn chain_of_trusted_proxies_is_followed() {
+        let origin = origin(
+            PROXY,
+            &[(
+                "forwarded",
+                "host=evil.com, for=203.0.113.7;proto=https;host=example.com, \
+                 for=\"10.0.0.2:8080\";proto=http;host=internal",
+            )],
+        );
+        assert_eq!(origin.url("/a"), "https://example.com/a");
+    }
+
+    #[test]
+    fn forwarded_for_parses_ports_and_ipv6() {
+        assert_eq!(forwarded_for("for=10.0.0.2:80"), "10.0.0.2".parse().ok());
+        assert_eq!(
+            forwarded_for("for=\"[2001:db8::1]:80\""),
+            "2001:db8::1".parse().ok()
+        );
+        assert_eq!(forwarded_for("for=2001:db8::1"), "2001:db8::1".parse().ok());
+        assert_eq!(forwarded_for("for=_hidden"), None);
+    }
+}
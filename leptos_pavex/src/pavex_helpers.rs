@@ -4,6 +4,7 @@ use leptos::{
     IntoView,
 };
 use leptos_meta::ServerMetaContextOutput;
+use pavex::request::RequestHead;
 use reactive_graph::owner::Owner;
 
 //A struct to hold the output of the app function closure so Pavex is happy
@@ -29,6 +30,15 @@ where
     AppFunction::new(any_view)
 }
 
+/// Build a GET RequestHead for a path, used when rendering the app outside of a real request
+pub(crate) fn stub_request_head(path: &str) -> RequestHead {
+    let request = http::Request::builder()
+        .uri(path)
+        .body(())
+        .expect("Failed to build a stub request");
+    request.into_parts().0.into()
+}
+
 /// This type holds the app's root root reactive Owner, which will be generated for each request,
 /// and differs between server functions and regular Leptos routes
 #[derive(Debug)]
@@ -0,0 +1,46 @@
+use crate::render_options::RenderOptions;
+use crate::route_table::PavexRouteTable;
+use leptos::config::LeptosOptions;
+
+/// The singletons `render_route`, `render_route_head` and `render_fallback` render with: the route
+/// table they look the matched route up in, the `LeptosOptions` with the site root the static
+/// pages are served from, and the `RenderOptions`.
+///
+/// `LeptosKit` registers its constructor, which takes the three singletons registered with the
+/// blueprint, so replacing one of them with another constructor is enough for the handlers to
+/// render with it.
+#[derive(Clone)]
+pub struct RenderConfig {
+    routes: PavexRouteTable,
+    options: LeptosOptions,
+    render_options: RenderOptions,
+}
+
+impl RenderConfig {
+    pub fn new(
+        routes: &PavexRouteTable,
+        options: &LeptosOptions,
+        render_options: &RenderOptions,
+    ) -> Self {
+        Self {
+            routes: routes.clone(),
+            options: options.clone(),
+            render_options: render_options.clone(),
+        }
+    }
+
+    /// The routes generated from the Leptos router.
+    pub fn routes(&self) -> &PavexRouteTable {
+        &self.routes
+    }
+
+    /// The options of the Leptos site.
+    pub fn options(&self) -> &LeptosOptions {
+        &self.options
+    }
+
+    /// How routes are rendered.
+    pub fn render_options(&self) -> &RenderOptions {
+        &self.render_options
+    }
+}
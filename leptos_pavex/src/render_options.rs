@@ -0,0 +1,257 @@
+use crate::cache::{self, CacheKey, ResponseCache};
+use crate::deadline::RenderDeadline;
+use crate::flush_policy::FlushPolicy;
+use bytes::Bytes;
+use leptos::tachys::{renderer::dom::Dom, view::any_view::AnyView, view::RenderHtml};
+use pavex::http::header::CONTENT_TYPE;
+use pavex::http::{HeaderName, HeaderValue, StatusCode};
+use pavex::request::RequestHead;
+use pavex::response::body::raw::Full;
+use pavex::response::Response;
+use reactive_graph::owner::Owner;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The HTML comment a streamed response ends with when the app panics after the first chunk has
+/// been sent, since its status can't be changed anymore.
+pub const RENDER_PANIC_MARKER: &str = "<!--leptos_pavex: the app panicked while rendering-->";
+
+/// Options that change how leptos_pavex renders the app for each request.
+///
+/// These are meant to be built once and registered as a singleton in your blueprint. The defaults
+/// can be registered as they are:
+/// ```rust,ignore
+/// bp.singleton(f!(leptos_pavex::render_options::RenderOptions::new));
+/// ```
+/// or you can register your own constructor to change them:
+/// ```rust,ignore
+/// pub fn render_options() -> RenderOptions {
+///     RenderOptions::new().with_error_page(|error| {
+///         view! { <ServerError message=error.message().to_string()/> }.into_any()
+///     })
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct RenderOptions {
+    error_page: Option<ErrorPage>,
+    cache: Option<Arc<dyn ResponseCache>>,
+    cache_vary: Vec<HeaderName>,
+    flush_policy: FlushPolicy,
+    render_deadline: Option<RenderDeadline>,
+    route_deadlines: HashMap<String, RenderDeadline>,
+    keep_fallback_status: bool,
+}
+
+impl RenderOptions {
+    /// Create the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render this component as the body of the response sent when the app fails to render. This
+    /// is a `500 Internal Server Error`, or the status set by a [`RenderDeadline`] when the app
+    /// takes too long to render.
+    ///
+    /// Streamed routes can only be replaced until their first chunk is sent. A panic after that,
+    /// in a `<Suspense/>` that resolves later, ends the stream with [`RENDER_PANIC_MARKER`].
+    pub fn with_error_page(
+        mut self,
+        error_page: impl Fn(&RenderError) -> AnyView<Dom> + Send + Sync + 'static,
+    ) -> Self {
+        self.error_page = Some(ErrorPage(Arc::new(error_page)));
+        self
+    }
+
+    /// Cache rendered responses in `cache`. Only responses to GET requests whose `Cache-Control`
+    /// header, usually set through `ResponseOptions`, has a `max-age` or `s-maxage` are cached,
+    /// for as long as it says.
+    pub fn with_response_cache(mut self, cache: impl ResponseCache + 'static) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Cache a separate response for each value of these request headers, on top of the path and
+    /// query of the request.
+    pub fn with_cache_vary(mut self, headers: impl IntoIterator<Item = HeaderName>) -> Self {
+        self.cache_vary = headers.into_iter().collect();
+        self
+    }
+
+    /// Set when the rendered HTML is flushed to the response while it's streamed.
+    pub fn with_flush_policy(mut self, flush_policy: FlushPolicy) -> Self {
+        self.flush_policy = flush_policy;
+        self
+    }
+
+    /// When the rendered HTML is flushed to the response while it's streamed.
+    pub fn flush_policy(&self) -> FlushPolicy {
+        self.flush_policy
+    }
+
+    /// Limit how long `SsrMode::Async` and `SsrMode::InOrder` routes can take to render.
+    pub fn with_render_deadline(mut self, deadline: RenderDeadline) -> Self {
+        self.render_deadline = Some(deadline);
+        self
+    }
+
+    /// Limit how long the route registered at a Pavex path pattern, such as `/products/:id`, can
+    /// take to render, in place of the deadline set by [`RenderOptions::with_render_deadline`].
+    pub fn with_route_deadline(
+        mut self,
+        path: impl Into<String>,
+        deadline: RenderDeadline,
+    ) -> Self {
+        self.route_deadlines.insert(path.into(), deadline);
+        self
+    }
+
+    /// The render deadline for the route registered at a Pavex path pattern, if it has one.
+    pub fn render_deadline(&self, path: &str) -> Option<RenderDeadline> {
+        self.route_deadlines
+            .get(path)
+            .copied()
+            .or(self.render_deadline)
+    }
+
+    /// Don't respond with a `404 Not Found` when the Leptos router renders its fallback, leaving
+    /// the status up to the app.
+    pub fn without_fallback_not_found(mut self) -> Self {
+        self.keep_fallback_status = true;
+        self
+    }
+
+    /// Whether a `404 Not Found` is sent when the Leptos router renders its fallback. The app can
+    /// still set another status through `ResponseOptions`.
+    pub fn fallback_not_found(&self) -> bool {
+        !self.keep_fallback_status
+    }
+
+    /// The cache key for a request, if responses to it should be cached.
+    pub fn cache_key(&self, req_head: &RequestHead) -> Option<CacheKey> {
+        self.cache
+            .as_ref()
+            .filter(|_| cache::is_cacheable(req_head))
+            .map(|_| CacheKey::new(req_head, &self.cache_vary))
+    }
+
+    /// Get the cached response for a key, if there's one that hasn't expired.
+    pub fn cached_response(&self, key: &CacheKey) -> Option<Response> {
+        cache::lookup(self.cache.as_deref()?, key)
+    }
+
+    /// Store a rendered response in the cache if it can be cached, returning a response with the
+    /// same contents.
+    pub async fn cache_response(&self, key: CacheKey, res: Response) -> Response {
+        match self.cache.as_deref() {
+            Some(response_cache) => cache::store(response_cache, key, res).await,
+            None => res,
+        }
+    }
+
+    /// Build the response for a render failure, with the status of the error, rendering the error
+    /// page if one was set.
+    pub fn error_response(&self, error: &RenderError) -> Response {
+        let html = self.error_page.as_ref().and_then(|error_page| {
+            let owner = Owner::new();
+            let html = catch_unwind(AssertUnwindSafe(|| {
+                owner.with(|| (error_page.0)(error).to_html())
+            }));
+            match html {
+                Ok(html) => Some(html),
+                Err(payload) => {
+                    tracing::error!(
+                        panic = %panic_message(payload.as_ref()),
+                        "Panicked while rendering the error page"
+                    );
+                    None
+                }
+            }
+        });
+
+        match html {
+            Some(html) => Response::new(error.status())
+                .insert_header(
+                    CONTENT_TYPE,
+                    HeaderValue::from_static("text/html; charset=utf-8"),
+                )
+                .set_raw_body(Full::new(Bytes::from(html))),
+            None => Response::new(error.status()),
+        }
+    }
+}
+
+impl Debug for RenderOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RenderOptions")
+            .field("error_page", &self.error_page.is_some())
+            .field("cache", &self.cache.is_some())
+            .field("cache_vary", &self.cache_vary)
+            .field("flush_policy", &self.flush_policy)
+            .field("render_deadline", &self.render_deadline)
+            .field("route_deadlines", &self.route_deadlines)
+            .field("fallback_not_found", &self.fallback_not_found())
+            .finish()
+    }
+}
+
+type ErrorPageFn = dyn Fn(&RenderError) -> AnyView<Dom> + Send + Sync;
+
+/// A component rendered in place of the app when it fails to render.
+#[derive(Clone)]
+struct ErrorPage(Arc<ErrorPageFn>);
+
+/// Why the app failed to render, passed to the error page.
+#[derive(Clone, Debug)]
+pub struct RenderError {
+    message: String,
+    status: StatusCode,
+}
+
+impl RenderError {
+    /// Create a render error from a message.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Create a render error for an app that didn't render within `timeout`, to be sent with
+    /// `status`.
+    pub fn timeout(timeout: Duration, status: StatusCode) -> Self {
+        Self {
+            message: format!("Rendering took longer than {timeout:?}"),
+            status,
+        }
+    }
+
+    /// Create a render error from the payload of a panic.
+    pub fn from_panic(payload: &(dyn Any + Send)) -> Self {
+        Self::new(panic_message(payload))
+    }
+
+    /// A description of the failure. For panics, this is the panic message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The status of the response sent for the failure.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+}
+
+/// Get the message out of a panic payload, if it has one.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
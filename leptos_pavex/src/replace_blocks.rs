@@ -0,0 +1,218 @@
+use any_spawner::Executor;
+use futures::{FutureExt, StreamExt};
+use leptos_integration_utils::PinnedStream;
+
+const TEMPLATE_OPEN: &str = "<template id=\"";
+const TEMPLATE_CLOSE: &str = "</template>";
+const SCRIPT_OPEN: &str = "<script";
+const SCRIPT_CLOSE: &str = "</script>";
+
+/// Takes an out-of-order HTML stream and pulls every chunk that is already available off of it,
+/// splicing any `<Suspense/>` fragments that have resolved by then into the shell in place of
+/// their fallbacks. The rest of the stream is passed through untouched, so fragments that are still
+/// pending keep being streamed out of order.
+///
+/// This should be called once the blocking resources have resolved, so that their fragments are
+/// part of the first chunk the client receives and don't need JavaScript to show up.
+pub async fn replace_blocks(stream: PinnedStream<String>) -> PinnedStream<String> {
+    let mut stream = stream.fuse();
+    let mut shell = String::new();
+    loop {
+        match stream.next().now_or_never() {
+            Some(Some(chunk)) => shell.push_str(&chunk),
+            Some(None) => break,
+            // The fragments of resolved resources are only pushed once the executor has run the
+            // effects waking their `<Suspense/>` up, so the stream gets a tick to catch up before
+            // the rest of it is taken as pending
+            None => {
+                Executor::tick().await;
+                match stream.next().now_or_never() {
+                    Some(Some(chunk)) => shell.push_str(&chunk),
+                    _ => break,
+                }
+            }
+        }
+    }
+    let shell = splice_fragments(&shell);
+    Box::pin(futures::stream::once(async move { shell }).chain(stream))
+}
+
+/// Replaces every out-of-order fragment in `html` whose fallback markers are also present in `html`.
+///
+/// An out-of-order fragment is made of a `<template id="{id}f">` holding the resolved HTML, followed
+/// by the `<script>` that would move it between the `<!--s-{id}o-->` and `<!--s-{id}c-->` markers
+/// on the client. Here we do that move on the server, and drop the template and the script.
+pub fn splice_fragments(html: &str) -> String {
+    let mut html = html.to_string();
+    let mut cursor = 0;
+
+    while let Some(found) = html[cursor..].find(TEMPLATE_OPEN) {
+        let template_start = cursor + found;
+        let Some(fragment) = Fragment::parse(&html, template_start) else {
+            cursor = template_start + TEMPLATE_OPEN.len();
+            continue;
+        };
+
+        let opening = format!("<!--s-{}o-->", fragment.id);
+        let closing = format!("<!--s-{}c-->", fragment.id);
+        let markers = html[..template_start]
+            .find(&opening)
+            .and_then(|open_start| {
+                html[open_start..template_start]
+                    .find(&closing)
+                    .map(|close_start| (open_start, open_start + close_start))
+            });
+        let Some((open_start, close_start)) = markers else {
+            cursor = fragment.end;
+            continue;
+        };
+
+        let replacement = if fragment.replace {
+            &html[fragment.content.clone()]
+        } else {
+            // the fragment resolved to nothing, so the fallback stays where it is
+            &html[open_start + opening.len()..close_start]
+        };
+
+        let mut spliced = String::with_capacity(html.len());
+        spliced.push_str(&html[..open_start]);
+        spliced.push_str(replacement);
+        spliced.push_str(&html[close_start + closing.len()..template_start]);
+        spliced.push_str(&html[fragment.end..]);
+        html = spliced;
+
+        // the fragment may contain markers for nested fragments that come later in the stream
+        cursor = open_start;
+    }
+
+    html
+}
+
+/// The position of an out-of-order fragment inside a chunk of HTML.
+struct Fragment {
+    id: String,
+    content: std::ops::Range<usize>,
+    replace: bool,
+    end: usize,
+}
+
+impl Fragment {
+    fn parse(html: &str, template_start: usize) -> Option<Self> {
+        let id_start = template_start + TEMPLATE_OPEN.len();
+        let id_len = html[id_start..].find("f\">")?;
+        let id = &html[id_start..id_start + id_len];
+        if id.is_empty() || id.contains('"') {
+            return None;
+        }
+
+        let content_start = id_start + id_len + "f\">".len();
+        let content_end = Self::find_template_close(html, content_start)?;
+        let script_start = content_end + TEMPLATE_CLOSE.len();
+        if !html[script_start..].starts_with(SCRIPT_OPEN) {
+            return None;
+        }
+        let script_len = html[script_start..].find(SCRIPT_CLOSE)?;
+        let script = &html[script_start..script_start + script_len];
+        if !script.contains(&format!("let id = \"{id}\"")) {
+            return None;
+        }
+
+        Some(Self {
+            id: id.to_string(),
+            content: content_start..content_end,
+            replace: script.contains("deleteContents()"),
+            end: script_start + script_len + SCRIPT_CLOSE.len(),
+        })
+    }
+
+    /// Finds the `</template>` that closes the template whose content starts at `from`, skipping
+    /// over any templates nested inside of it.
+    fn find_template_close(html: &str, from: usize) -> Option<usize> {
+        let mut depth = 0;
+        let mut cursor = from;
+        loop {
+            let close = cursor + html[cursor..].find(TEMPLATE_CLOSE)?;
+            match html[cursor..close].find("<template") {
+                Some(open) => {
+                    depth += 1;
+                    cursor += open + "<template".len();
+                }
+                None if depth == 0 => return Some(close),
+                None => {
+                    depth -= 1;
+                    cursor = close + TEMPLATE_CLOSE.len();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragment(id: &str, content: &str, replace: bool) -> String {
+        let update = if replace {
+            "range.deleteContents(); let tpl = document.getElementById(`${id}f`);"
+        } else {
+            "close.remove();open.remove();"
+        };
+        format!(
+            "<template id=\"{id}f\">{content}</template><script>(function() {{ let id = \
+             \"{id}\";{update}}})()</script>"
+        )
+    }
+
+    #[test]
+    fn resolved_fragment_replaces_fallback() {
+        let html = format!(
+            "<main><!--s-1-o--><p>Loading...</p><!--s-1-c--></main>{}",
+            fragment("1-", "<p>Loaded</p>", true)
+        );
+        assert_eq!(splice_fragments(&html), "<main><p>Loaded</p></main>");
+    }
+
+    #[test]
+    fn empty_fragment_keeps_fallback() {
+        let html = format!(
+            "<main><!--s-1-o--><p>Loading...</p><!--s-1-c--></main>{}",
+            fragment("1-", "", false)
+        );
+        assert_eq!(splice_fragments(&html), "<main><p>Loading...</p></main>");
+    }
+
+    #[test]
+    fn nested_fragments_are_replaced() {
+        let html = format!(
+            "<main><!--s-1-o-->outer fallback<!--s-1-c--></main>{}{}",
+            fragment(
+                "1-",
+                "<div><!--s-1-0-o-->inner fallback<!--s-1-0-c--></div>",
+                true
+            ),
+            fragment("1-0-", "inner", true)
+        );
+        assert_eq!(splice_fragments(&html), "<main><div>inner</div></main>");
+    }
+
+    #[test]
+    fn fragment_without_markers_is_left_alone() {
+        let html = format!("<main></main>{}", fragment("2-", "late", true));
+        assert_eq!(splice_fragments(&html), html);
+    }
+
+    #[test]
+    fn user_templates_are_left_alone() {
+        let html = "<template id=\"rowf\"><tr></tr></template><p>after</p>";
+        assert_eq!(splice_fragments(html), html);
+    }
+
+    #[test]
+    fn templates_inside_fragments_are_kept() {
+        let html = format!(
+            "<!--s-1-o-->fallback<!--s-1-c-->{}",
+            fragment("1-", "<template><b>row</b></template>", true)
+        );
+        assert_eq!(splice_fragments(&html), "<template><b>row</b></template>");
+    }
+}
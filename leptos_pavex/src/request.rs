@@ -1,11 +1,17 @@
+use crate::response_options::ResponseOptions;
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
-use http_body_util::BodyExt;
+use http_body::Body;
+use http_body_util::{BodyExt, LengthLimitError, Limited};
+use leptos::prelude::use_context;
 use leptos::server_fn::{error::ServerFnError, request::Req};
+use pavex::http::header::ACCEPT;
+use pavex::http::StatusCode;
+use pavex::request::body::errors::ExtractBufferedBodyError;
 use pavex::request::body::{BodySizeLimit, BufferedBody, RawIncomingBody};
 use pavex::request::RequestHead;
 use std::borrow::Cow;
-
+use std::error::Error;
 
 /// Generate a Request type, as Pavex does not provide one and Leptos expects one to exist
 /// Provided to the Leptos Context for each Request
@@ -13,10 +19,37 @@ use std::borrow::Cow;
 pub struct PavexRequest {
     pub head: RequestHead,
     pub body: RawIncomingBody,
+    pub body_size_limit: BodySizeLimit,
 }
 impl PavexRequest {
     pub fn new_from_req(head: RequestHead, body: RawIncomingBody) -> Self {
-        Self { head, body }
+        Self {
+            head,
+            body,
+            body_size_limit: BodySizeLimit::default(),
+        }
+    }
+
+    /// Limit the size of the body read by the server function.
+    pub fn with_body_size_limit(mut self, body_size_limit: BodySizeLimit) -> Self {
+        self.body_size_limit = body_size_limit;
+        self
+    }
+
+    /// Read the whole body, up to the size limit. Going over it sets a `413 Payload Too Large`
+    /// status on the ResponseOptions of the request.
+    async fn buffer_body<CustErr>(self) -> Result<Bytes, ServerFnError<CustErr>> {
+        match BufferedBody::extract(&self.head, self.body, self.body_size_limit).await {
+            Ok(buf) => Ok(buf.bytes),
+            Err(e) => {
+                if let ExtractBufferedBodyError::SizeLimitExceeded(_) = e {
+                    if let Some(res_options) = use_context::<ResponseOptions>() {
+                        res_options.set_status(StatusCode::PAYLOAD_TOO_LARGE);
+                    }
+                }
+                Err(ServerFnError::Deserialization(e.to_string()))
+            }
+        }
     }
 }
 
@@ -39,7 +72,7 @@ where
     fn accepts(&self) -> Option<Cow<'_, str>> {
         let headers = &self.head.headers;
         headers
-            .get("Acceot")
+            .get(ACCEPT)
             .map(|h| String::from_utf8_lossy(h.as_bytes()))
     }
 
@@ -51,17 +84,12 @@ where
     }
 
     async fn try_into_bytes(self) -> Result<Bytes, ServerFnError<CustErr>> {
-        let buf = BufferedBody::extract(&self.head, self.body, BodySizeLimit::Disabled)
-            .await
-            .map_err(|e| ServerFnError::Deserialization(e.to_string()))?;
-        Ok(buf.bytes)
+        self.buffer_body().await
     }
 
     async fn try_into_string(self) -> Result<String, ServerFnError<CustErr>> {
-        let buf = BufferedBody::extract(&self.head, self.body, BodySizeLimit::Disabled)
-            .await
-            .map_err(|e| ServerFnError::Deserialization(e.to_string()))?;
-        String::from_utf8(Vec::from(buf.bytes))
+        let bytes = self.buffer_body().await?;
+        String::from_utf8(Vec::from(bytes))
             .map_err(|e| ServerFnError::Deserialization(e.to_string()))
     }
 
@@ -71,9 +99,115 @@ where
         impl Stream<Item = Result<Bytes, ServerFnError>> + Send + 'static,
         ServerFnError<CustErr>,
     > {
-        Ok(self
-            .body
-            .into_data_stream()
-            .map(|chunk| chunk.map_err(|e| ServerFnError::Deserialization(e.to_string()))))
+        Ok(limit_stream(
+            self.body,
+            self.body_size_limit,
+            use_context::<ResponseOptions>(),
+        ))
+    }
+}
+
+/// Stream the chunks of a body, up to the size limit. Going over it ends the stream with an error,
+/// and sets a `413 Payload Too Large` status on the ResponseOptions, like for buffered bodies.
+fn limit_stream<B>(
+    body: B,
+    body_size_limit: BodySizeLimit,
+    res_options: Option<ResponseOptions>,
+) -> impl Stream<Item = Result<Bytes, ServerFnError>> + Send + 'static
+where
+    B: Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<Box<dyn Error + Send + Sync>>,
+{
+    let max_size = match body_size_limit {
+        BodySizeLimit::Enabled { max_size } => {
+            usize::try_from(max_size.as_u64()).unwrap_or(usize::MAX)
+        }
+        BodySizeLimit::Disabled => usize::MAX,
+    };
+    Limited::new(body, max_size)
+        .into_data_stream()
+        .map(move |chunk| {
+            chunk.map_err(|e| {
+                if e.is::<LengthLimitError>() {
+                    if let Some(res_options) = &res_options {
+                        res_options.set_status(StatusCode::PAYLOAD_TOO_LARGE);
+                    }
+                }
+                ServerFnError::Deserialization(e.to_string())
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::stream;
+    use http_body::Frame;
+    use http_body_util::StreamBody;
+    use pavex::unit::ToByteUnit;
+    use std::convert::Infallible;
+
+    fn body(chunks: &[&'static str]) -> impl Body<Data = Bytes, Error = Infallible> + Send {
+        StreamBody::new(stream::iter(
+            chunks
+                .iter()
+                .map(|chunk| Ok(Frame::data(Bytes::from_static(chunk.as_bytes()))))
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    fn limit(max_size: u64) -> BodySizeLimit {
+        BodySizeLimit::Enabled {
+            max_size: max_size.bytes(),
+        }
+    }
+
+    #[test]
+    fn stream_within_the_limit_is_passed_through() {
+        let res_options = ResponseOptions::default();
+        let chunks = block_on(
+            limit_stream(body(&["ab", "cd"]), limit(4), Some(res_options.clone()))
+                .collect::<Vec<_>>(),
+        );
+
+        let chunks = chunks.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(chunks, ["ab", "cd"]);
+        assert_eq!(res_options.status(), None);
+    }
+
+    #[test]
+    fn stream_over_the_limit_ends_with_an_error_and_a_413() {
+        let res_options = ResponseOptions::default();
+        let chunks = block_on(
+            limit_stream(
+                body(&["ab", "cd", "ef"]),
+                limit(5),
+                Some(res_options.clone()),
+            )
+            .collect::<Vec<_>>(),
+        );
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].as_ref().unwrap(), "ab");
+        assert_eq!(chunks[1].as_ref().unwrap(), "cd");
+        assert!(chunks[2].is_err());
+        assert_eq!(res_options.status(), Some(StatusCode::PAYLOAD_TOO_LARGE));
+    }
+
+    #[test]
+    fn disabled_limit_streams_the_whole_body() {
+        let res_options = ResponseOptions::default();
+        let chunks = block_on(
+            limit_stream(
+                body(&["ab", "cd", "ef"]),
+                BodySizeLimit::Disabled,
+                Some(res_options.clone()),
+            )
+            .collect::<Vec<_>>(),
+        );
+
+        assert!(chunks.iter().all(Result::is_ok));
+        assert_eq!(res_options.status(), None);
     }
 }
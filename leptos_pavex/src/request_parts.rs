@@ -1,5 +1,9 @@
 // use spin_sdk::http::{conversions::IntoHeaders, IncomingRequest, Method, Scheme};
-use pavex::http::{uri::Scheme, HeaderMap, Method};
+use crate::origin::RequestOrigin;
+use pavex::http::{
+    uri::{Authority, Scheme},
+    HeaderMap, Method,
+};
 use pavex::request::RequestHead;
 
 /// A convenience type that's provided to the Leptos context containing info about the incoming Request
@@ -8,6 +12,7 @@ use pavex::request::RequestHead;
 pub struct RequestParts {
     method: Method,
     scheme: Option<Scheme>,
+    host: Option<Authority>,
     headers: HeaderMap,
 }
 impl RequestParts {
@@ -16,6 +21,7 @@ impl RequestParts {
             method: Method::default(),
             headers: HeaderMap::default(),
             scheme: None,
+            host: None,
         }
     }
 
@@ -23,9 +29,18 @@ impl RequestParts {
         Self {
             method: req.method.clone(),
             scheme: req.target.scheme().cloned(),
+            host: RequestOrigin::untrusted(req).host().cloned(),
             headers: req.headers.clone(),
         }
     }
+
+    /// Take the scheme and host from the origin the client used, rather than from the request
+    /// the server received.
+    pub fn with_origin(mut self, origin: &RequestOrigin) -> Self {
+        self.scheme = Some(origin.scheme().clone());
+        self.host = origin.host().cloned();
+        self
+    }
     /// Get the Headers for the Request
     pub fn headers(&self) -> &HeaderMap {
         &self.headers
@@ -38,4 +53,8 @@ impl RequestParts {
     pub fn scheme(&self) -> &Option<Scheme> {
         &self.scheme
     }
+    /// Get the Host the Request was sent to
+    pub fn host(&self) -> &Option<Authority> {
+        &self.host
+    }
 }
@@ -1,18 +1,21 @@
+use crate::error_status::ErrorStatuses;
 use crate::extend_response::ExtendResponse;
 use crate::pavex_helpers::AppFunction;
+use crate::request_parts::RequestParts;
 use crate::response_options::ResponseOptions;
 use crate::stream::{LeptosPavexStream, PavexStream};
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
 use leptos::nonce::use_nonce;
+use leptos::prelude::use_context;
 use leptos::server_fn::error::{
     ServerFnError, ServerFnErrorErr, ServerFnErrorSerde, SERVER_FN_ERROR_HEADER,
 };
 use leptos::server_fn::response::Res;
 use leptos_integration_utils::{BoxedFnOnce, PinnedFuture, PinnedStream};
-use pavex::http::header::CONTENT_TYPE;
+use pavex::http::header::{CONTENT_TYPE, LOCATION};
 use pavex::http::HeaderValue;
-use pavex::http::{HeaderMap, HeaderName, StatusCode};
+use pavex::http::{HeaderMap, HeaderName, Method, StatusCode};
 use pavex::response::Response;
 use reactive_graph::owner::{Owner, Sandboxed};
 use std::pin::Pin;
@@ -58,10 +61,9 @@ impl ExtendResponse for PavexResponse {
 pub fn build_response(
     app_fn: AppFunction,
     additional_context: impl FnOnce() + Send + 'static,
-    stream_builder: fn(
-        AppFunction,
-        BoxedFnOnce<PinnedStream<String>>,
-    ) -> PinnedFuture<PinnedStream<String>>,
+    stream_builder: impl FnOnce(AppFunction, BoxedFnOnce<PinnedStream<String>>) -> PinnedFuture<PinnedStream<String>>
+        + Send
+        + 'static,
 ) -> (Owner, PinnedFuture<PinnedStream<String>>) {
     let Some(owner) = Owner::current() else {
         panic!("Failed to get Owner for components!");
@@ -85,12 +87,12 @@ pub fn build_response(
                 let chunks = Box::new({
                     let shared_context = shared_context.clone();
                     move || {
-                        Box::pin(shared_context.pending_data().unwrap().map(
-                            move |chunk| {
-                                format!("<script{nonce}>{chunk}</script>")
-                            },
-                        ))
-                            as Pin<Box<dyn Stream<Item = String> + Send>>
+                        Box::pin(
+                            shared_context
+                                .pending_data()
+                                .unwrap()
+                                .map(move |chunk| format!("<script{nonce}>{chunk}</script>")),
+                        ) as Pin<Box<dyn Stream<Item = String> + Send>>
                     }
                 });
 
@@ -111,8 +113,6 @@ pub fn build_response(
     (owner, stream)
 }
 
-
-
 impl<CustErr> Res<CustErr> for PavexResponse
 where
     CustErr: Send + Sync + Debug + FromStr + Display + 'static,
@@ -165,7 +165,11 @@ where
     }
 
     fn error_response(path: &str, err: &ServerFnError<CustErr>) -> Self {
-        let res = Response::new(StatusCode::INTERNAL_SERVER_ERROR)
+        // Only the status depends on the error, the body is the serialized error the client expects
+        let status = use_context::<ErrorStatuses>()
+            .unwrap_or_default()
+            .status_of(err);
+        let res = Response::new(status)
             .insert_header(
                 HeaderName::from_static(SERVER_FN_ERROR_HEADER),
                 HeaderValue::from_str(path).unwrap(),
@@ -174,11 +178,23 @@ where
         PavexResponse(res)
     }
 
-    fn redirect(&mut self, _path: &str) {
-        //TODO: Enabling these seems to override location header
-        // not sure what's causing that
-        //let res_options = expect_context::<ResponseOptions>();
-        //res_options.insert_header("Location", path);
-        //res_options.set_status(302);
+    fn redirect(&mut self, path: &str) {
+        let Ok(location) = HeaderValue::from_str(path) else {
+            tracing::warn!("Failed to redirect to {path:?}, it isn't a valid Location");
+            return;
+        };
+        // A Location set through ResponseOptions is applied after this one, and wins over it
+        self.0.headers_mut().insert(LOCATION, location);
+        let method = use_context::<RequestParts>().map(|parts| parts.method().clone());
+        *self.0.status_mut() = form_redirect_status(method.as_ref());
+    }
+}
+
+/// The status of a redirect answering a plain HTML form. Forms sent with another method than GET
+/// get a `303 See Other`, so that the browser follows the redirect with a GET.
+pub fn form_redirect_status(method: Option<&Method>) -> StatusCode {
+    match method {
+        Some(method) if *method == Method::GET || *method == Method::HEAD => StatusCode::FOUND,
+        _ => StatusCode::SEE_OTHER,
     }
 }
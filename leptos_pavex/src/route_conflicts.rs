@@ -0,0 +1,313 @@
+use crate::mount::MountPoint;
+use crate::PavexRouteList;
+use http::Method;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Routes registered in the blueprint by hand, next to the ones generated from the Leptos router.
+/// Passing them to `add_leptos_routes_with_reserved` checks that the two don't conflict:
+/// ```rust,ignore
+/// let reserved = ReservedRoutes::new()
+///     .with_route(Method::GET, "/api/ping")
+///     .with_route(Method::GET, "/*path");
+/// add_leptos_routes_with_reserved(&routes, &reserved, bp);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ReservedRoutes {
+    routes: Vec<(Option<Method>, String)>,
+}
+
+impl ReservedRoutes {
+    /// Create an empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve a Pavex path for one method.
+    pub fn with_route(mut self, method: Method, path: impl Into<String>) -> Self {
+        self.routes.push((Some(method), path.into()));
+        self
+    }
+
+    /// Reserve a Pavex path for every method.
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.routes.push((None, path.into()));
+        self
+    }
+}
+
+/// Two routes that can't both be registered in the Pavex router.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouteConflict {
+    /// The path generated from the Leptos router, or registered for a server function.
+    pub leptos_path: String,
+    /// The path it conflicts with, either reserved or generated.
+    pub other_path: String,
+    /// Why the two conflict.
+    pub reason: String,
+}
+
+impl Display for RouteConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the Leptos route `{}` conflicts with `{}`: {}",
+            self.leptos_path, self.other_path, self.reason
+        )
+    }
+}
+
+/// Every conflict found between the generated routes and the reserved ones.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouteConflicts(pub Vec<RouteConflict>);
+
+impl Display for RouteConflicts {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Found {} conflicting route(s):", self.0.len())?;
+        for conflict in &self.0 {
+            writeln!(f, "  - {conflict}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for RouteConflicts {}
+
+/// Check the routes generated from the Leptos router and the server functions registered under
+/// `mount` against each other and against the reserved routes, for the conflicts that would make
+/// the Pavex router reject them or match them ambiguously.
+pub fn check_route_conflicts(
+    routes: &PavexRouteList,
+    reserved: &ReservedRoutes,
+    mount: &MountPoint,
+) -> Result<(), RouteConflicts> {
+    let server_fns = crate::server_fn::server_fn_paths()
+        .map(|(path, method)| (mount.join(path), method))
+        .collect::<Vec<_>>();
+    find_conflicts(routes, &server_fns, reserved)
+}
+
+fn find_conflicts(
+    routes: &PavexRouteList,
+    server_fns: &[(String, Method)],
+    reserved: &ReservedRoutes,
+) -> Result<(), RouteConflicts> {
+    // Every path registered for the generated routes, with the methods registered at it
+    let mut generated = routes
+        .iter()
+        .flat_map(|listing| {
+            let methods = listing.allowed_methods();
+            std::iter::once(listing.path().to_string())
+                .chain(listing.alternate_path())
+                .map(move |path| (path, methods.clone()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    // Server functions sharing a path share the route answering their preflight requests too
+    let mut server_fn_methods = BTreeMap::<&str, Vec<Method>>::new();
+    for (path, method) in server_fns {
+        server_fn_methods
+            .entry(path)
+            .or_insert_with(|| vec![Method::OPTIONS])
+            .push(method.clone());
+    }
+    generated.extend(
+        server_fn_methods
+            .into_iter()
+            .map(|(path, methods)| (path.to_string(), methods)),
+    );
+
+    let mut conflicts = Vec::new();
+    for (index, (path, methods)) in generated.iter().enumerate() {
+        for (other_path, other_methods) in &generated[index + 1..] {
+            if let Some(reason) = conflict(
+                path,
+                other_path,
+                |method| other_methods.contains(method),
+                methods,
+            ) {
+                conflicts.push(RouteConflict {
+                    leptos_path: path.clone(),
+                    other_path: other_path.clone(),
+                    reason,
+                });
+            }
+        }
+        for (method, other_path) in &reserved.routes {
+            let overlaps = |m: &Method| method.is_none() || method.as_ref() == Some(m);
+            if let Some(reason) = conflict(path, other_path, overlaps, methods) {
+                conflicts.push(RouteConflict {
+                    leptos_path: path.clone(),
+                    other_path: other_path.clone(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    if conflicts.is_empty() {
+        Ok(())
+    } else {
+        Err(RouteConflicts(conflicts))
+    }
+}
+
+/// Why two paths conflict, if they do. `overlaps` tells whether the other route is registered for
+/// one of `methods`.
+fn conflict(
+    path: &str,
+    other: &str,
+    overlaps: impl Fn(&Method) -> bool,
+    methods: &[Method],
+) -> Option<String> {
+    let segments = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    let other_segments = other
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+
+    // Params and catch-alls in the same position, after the same segments, must be identical
+    for (segment, other_segment) in segments.iter().zip(other_segments.iter()) {
+        if segment == other_segment {
+            continue;
+        }
+        return (is_wildcard(segment) && is_wildcard(other_segment)).then(|| {
+            format!(
+                "`{segment}` and `{other_segment}` match the same segment under different names"
+            )
+        });
+    }
+
+    // The same path can only be registered once for each method
+    let same_shape =
+        segments.len() == other_segments.len() && path.ends_with('/') == other.ends_with('/');
+    if same_shape {
+        let shared = methods
+            .iter()
+            .filter(|method| overlaps(method))
+            .map(Method::as_str)
+            .collect::<Vec<_>>();
+        if !shared.is_empty() {
+            return Some(format!("both are registered for {}", shared.join(", ")));
+        }
+    }
+    None
+}
+
+/// Whether a segment is a param or a catch-all.
+fn is_wildcard(segment: &str) -> bool {
+    segment.starts_with(':') || segment.starts_with('*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PavexRouteListing;
+    use leptos_router::{Method as LeptosMethod, SsrMode};
+
+    fn routes(paths: &[&str]) -> PavexRouteList {
+        paths
+            .iter()
+            .map(|path| {
+                PavexRouteListing::new(path.to_string(), SsrMode::Async, [LeptosMethod::Get], None)
+            })
+            .collect()
+    }
+
+    fn server_fn(method: Method, path: &str) -> (String, Method) {
+        (path.to_string(), method)
+    }
+
+    fn conflicting_paths(result: Result<(), RouteConflicts>) -> Vec<(String, String)> {
+        result
+            .err()
+            .map(|RouteConflicts(conflicts)| conflicts)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|conflict| (conflict.leptos_path, conflict.other_path))
+            .collect()
+    }
+
+    #[test]
+    fn distinct_routes_do_not_conflict() {
+        let routes = routes(&["/", "/about", "/products/:id", "/products/new"]);
+        let server_fns = [
+            server_fn(Method::POST, "/api/add_todo"),
+            server_fn(Method::GET, "/api/get_todos"),
+        ];
+        let reserved = ReservedRoutes::new()
+            .with_route(Method::GET, "/api/ping")
+            .with_route(Method::GET, "/*path");
+        assert_eq!(find_conflicts(&routes, &server_fns, &reserved), Ok(()));
+    }
+
+    #[test]
+    fn params_named_differently_in_the_same_position_conflict() {
+        let routes = routes(&["/products/:id", "/products/:slug/reviews"]);
+        assert_eq!(
+            conflicting_paths(find_conflicts(&routes, &[], &ReservedRoutes::new())),
+            [(
+                "/products/:id".to_string(),
+                "/products/:slug/reviews".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn reserved_routes_only_conflict_for_the_same_method() {
+        let routes = routes(&["/contact"]);
+        let post = ReservedRoutes::new().with_route(Method::POST, "/contact");
+        assert_eq!(find_conflicts(&routes, &[], &post), Ok(()));
+        let get = ReservedRoutes::new().with_route(Method::GET, "/contact");
+        assert_eq!(
+            conflicting_paths(find_conflicts(&routes, &[], &get)),
+            [("/contact".to_string(), "/contact".to_string())]
+        );
+    }
+
+    #[test]
+    fn server_fns_conflict_with_reserved_routes() {
+        let server_fns = [server_fn(Method::POST, "/api/add_todo")];
+        let other_method = ReservedRoutes::new().with_route(Method::GET, "/api/add_todo");
+        assert_eq!(
+            find_conflicts(&Vec::new(), &server_fns, &other_method),
+            Ok(())
+        );
+        for reserved in [
+            ReservedRoutes::new().with_route(Method::POST, "/api/add_todo"),
+            ReservedRoutes::new().with_path("/api/add_todo"),
+        ] {
+            assert_eq!(
+                conflicting_paths(find_conflicts(&Vec::new(), &server_fns, &reserved)),
+                [("/api/add_todo".to_string(), "/api/add_todo".to_string())]
+            );
+        }
+    }
+
+    #[test]
+    fn server_fns_conflict_with_app_routes() {
+        let routes = routes(&["/api/add_todo"]);
+        let server_fns = [server_fn(Method::POST, "/api/add_todo")];
+        let conflicts = find_conflicts(&routes, &server_fns, &ReservedRoutes::new())
+            .unwrap_err()
+            .0;
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].reason, "both are registered for OPTIONS");
+    }
+
+    #[test]
+    fn server_fns_sharing_a_path_do_not_conflict() {
+        let server_fns = [
+            server_fn(Method::GET, "/api/todos"),
+            server_fn(Method::POST, "/api/todos"),
+        ];
+        assert_eq!(
+            find_conflicts(&Vec::new(), &server_fns, &ReservedRoutes::new()),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use crate::route_overrides::glob_matches;
+use crate::PavexRouteListing;
+use pavex::blueprint::Blueprint;
+use std::fmt::{Debug, Formatter};
+
+/// A subset of the routes generated from the Leptos router, registered in a nested blueprint with
+/// its own middleware. Routes are picked with glob patterns matched against their path in the app,
+/// without the prefix it's mounted under, like the ones of `RouteOverrides`, or with a predicate on
+/// the listing:
+/// ```rust,ignore
+/// let admin = RouteGroup::new(|bp| {
+///     bp.pre_process(f!(crate::auth::require_admin));
+/// })
+/// .matching("/admin/**");
+/// let slow = RouteGroup::new(|bp| {
+///     bp.wrap(f!(crate::rate_limit::limit));
+/// })
+/// .matching_listing(|listing| listing.mode() == SsrMode::Async);
+/// add_leptos_routes_with_groups(&routes, &reserved, &MountPoint::root(), &[admin, slow], bp);
+/// ```
+/// Each route is registered in the first group it matches, or in the blueprint itself if it
+/// matches none. The middleware applies to the HEAD and OPTIONS routes registered for it too, but
+/// not to the server functions.
+#[derive(Clone)]
+pub struct RouteGroup {
+    middleware: fn(&mut Blueprint),
+    patterns: Vec<String>,
+    predicates: Vec<fn(&PavexRouteListing) -> bool>,
+}
+
+impl RouteGroup {
+    /// Create a group matching no route, whose middleware is registered in the nested blueprint by
+    /// `middleware`, before its routes.
+    pub fn new(middleware: fn(&mut Blueprint)) -> Self {
+        Self {
+            middleware,
+            patterns: Vec::new(),
+            predicates: Vec::new(),
+        }
+    }
+
+    /// Add the routes whose path in the app matches a glob pattern to the group.
+    pub fn matching(mut self, pattern: impl Into<String>) -> Self {
+        self.patterns.push(pattern.into());
+        self
+    }
+
+    /// Add the routes for which a predicate holds to the group, to pick them by their mode,
+    /// methods or static rendering.
+    pub fn matching_listing(mut self, predicate: fn(&PavexRouteListing) -> bool) -> Self {
+        self.predicates.push(predicate);
+        self
+    }
+
+    /// Whether a route belongs to the group.
+    pub fn contains(&self, listing: &PavexRouteListing) -> bool {
+        let app_path = listing
+            .mount()
+            .strip(listing.path())
+            .unwrap_or_else(|| listing.path().to_string());
+        self.patterns
+            .iter()
+            .any(|pattern| glob_matches(pattern, &app_path))
+            || self.predicates.iter().any(|predicate| predicate(listing))
+    }
+
+    /// Register the group's middleware in a nested blueprint.
+    pub(crate) fn blueprint(&self) -> Blueprint {
+        let mut bp = Blueprint::new();
+        (self.middleware)(&mut bp);
+        bp
+    }
+}
+
+impl Debug for RouteGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RouteGroup")
+            .field("patterns", &self.patterns)
+            .field("predicates", &self.predicates.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::leptos_routes::add_leptos_routes_with_groups;
+    use crate::mount::MountPoint;
+    use crate::route_conflicts::ReservedRoutes;
+    use leptos_router::{Method, SsrMode};
+    use pavex::f;
+
+    fn listing(path: &str, mount: &MountPoint) -> PavexRouteListing {
+        PavexRouteListing::new(path.to_string(), SsrMode::OutOfOrder, [Method::Get], None)
+            .with_mount(mount.clone())
+    }
+
+    #[test]
+    fn patterns_match_the_path_in_the_app() {
+        let admin = RouteGroup::new(|_| {}).matching("/admin/**");
+        for mount in [MountPoint::root(), MountPoint::new("/app")] {
+            assert!(admin.contains(&listing("/admin/users", &mount)));
+            assert!(!admin.contains(&listing("/about", &mount)));
+        }
+    }
+
+    #[test]
+    fn patterns_do_not_match_the_mount_point() {
+        let app = RouteGroup::new(|_| {}).matching("/app/**");
+        assert!(!app.contains(&listing("/about", &MountPoint::new("/app"))));
+        assert!(app.contains(&listing("/app/about", &MountPoint::new("/app"))));
+    }
+
+    #[test]
+    fn predicates_match_the_listing() {
+        let async_routes =
+            RouteGroup::new(|_| {}).matching_listing(|listing| listing.mode() == SsrMode::Async);
+        let mount = MountPoint::root();
+        assert!(!async_routes.contains(&listing("/about", &mount)));
+        assert!(async_routes.contains(&PavexRouteListing::new(
+            "/reports".to_string(),
+            SsrMode::Async,
+            [Method::Get],
+            None
+        )));
+    }
+
+    #[test]
+    fn grouped_routes_are_registered_behind_the_group_middleware() {
+        let routes = vec![
+            listing("/about", &MountPoint::root()),
+            listing("/admin/users", &MountPoint::root()),
+        ];
+        let admin = RouteGroup::new(|bp| {
+            bp.pre_process(f!(crate::auth::require_admin));
+        })
+        .matching("/admin/**");
+        let mut bp = Blueprint::new();
+        add_leptos_routes_with_groups(
+            &routes,
+            &ReservedRoutes::new(),
+            &MountPoint::root(),
+            &[admin],
+            &mut bp,
+        );
+
+        // The blueprint has no accessors, but lists its components in order when persisted
+        let path = std::env::temp_dir().join("leptos_pavex_route_groups.ron");
+        bp.persist(&path).unwrap();
+        let persisted = std::fs::read_to_string(&path).unwrap();
+        let (top_level, nested) = persisted.split_once("NestedBlueprint(").unwrap();
+        assert!(top_level.contains(r#"path: "/about""#));
+        assert!(!top_level.contains("/admin/users"));
+        let middleware = nested.find("crate::auth::require_admin").unwrap();
+        let handler = nested.find(r#"path: "/admin/users""#).unwrap();
+        assert!(middleware < handler);
+        assert!(nested[handler..].contains("crate::render_route"));
+        assert!(!nested.contains("/about"));
+    }
+}
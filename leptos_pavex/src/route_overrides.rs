@@ -0,0 +1,204 @@
+use crate::{PavexRouteList, PavexRouteListing};
+use leptos_router::{Method as LeptosMethod, SsrMode};
+
+/// Changes to the routes generated from the Leptos router, applied to the route list without
+/// editing the component tree. Routes are picked with glob patterns matched against their Pavex
+/// path: `*` matches any part of a single segment, and `**` matches any number of segments. Params
+/// are matched as they're written, so `/products/*` matches `/products/:id`, while `/products/:id`
+/// and `/*path` only match those exact segments.
+///
+/// The route list is built twice, once for the blueprint and once when the server starts, and both
+/// have to go through the same overrides: a route excluded from the blueprint but not from the
+/// server would be rendered without a handler, and one with another mode would be served from a
+/// static file that was never generated:
+/// ```rust,ignore
+/// pub fn route_overrides() -> RouteOverrides {
+///     RouteOverrides::new()
+///         .exclude("/admin/**")
+///         .with_mode("/reports/*", SsrMode::Async)
+///         .with_methods("/contact", [Method::Get, Method::Post])
+/// }
+///
+/// let routes = route_overrides().apply(discover_routes(app_fn));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RouteOverrides {
+    exclusions: Vec<String>,
+    modes: Vec<(String, SsrMode)>,
+    methods: Vec<(String, Vec<LeptosMethod>)>,
+}
+
+impl RouteOverrides {
+    /// Create an empty set of overrides.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Leave the routes matching a pattern out of the list, so that they can be handled by a
+    /// custom handler.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclusions.push(pattern.into());
+        self
+    }
+
+    /// Render the routes matching a pattern with another `SsrMode`.
+    pub fn with_mode(mut self, pattern: impl Into<String>, mode: SsrMode) -> Self {
+        self.modes.push((pattern.into(), mode));
+        self
+    }
+
+    /// Register the routes matching a pattern for other HTTP methods.
+    pub fn with_methods(
+        mut self,
+        pattern: impl Into<String>,
+        methods: impl IntoIterator<Item = LeptosMethod>,
+    ) -> Self {
+        self.methods
+            .push((pattern.into(), methods.into_iter().collect()));
+        self
+    }
+
+    /// Apply the overrides to a route list. When several overrides match the same route, the last
+    /// one added wins.
+    pub fn apply(&self, routes: PavexRouteList) -> PavexRouteList {
+        routes
+            .into_iter()
+            .filter(|listing| !is_excluded(&self.exclusions, listing.path()))
+            .map(|listing| self.override_listing(listing))
+            .collect()
+    }
+
+    fn override_listing(&self, mut listing: PavexRouteListing) -> PavexRouteListing {
+        if let Some((_, mode)) = last_match(&self.modes, listing.path()) {
+            listing = listing.with_mode(*mode);
+        }
+        if let Some((_, methods)) = last_match(&self.methods, listing.path()) {
+            listing = listing.with_methods(methods.iter().copied());
+        }
+        listing
+    }
+}
+
+/// Whether a path matches any of the exclusion patterns.
+pub fn is_excluded(patterns: &[String], path: &str) -> bool {
+    patterns.iter().any(|pattern| glob_matches(pattern, path))
+}
+
+fn last_match<'a, T>(overrides: &'a [(String, T)], path: &str) -> Option<&'a (String, T)> {
+    overrides
+        .iter()
+        .rev()
+        .find(|(pattern, _)| glob_matches(pattern, path))
+}
+
+/// Match a Pavex path against a glob pattern, where `*` matches any part of a single segment and
+/// `**` matches any number of segments. Pattern segments that are Pavex params, like `:id` or
+/// `*path`, are matched exactly, so that exact exclusions keep working.
+pub fn glob_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    let path = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    segments_match(&pattern, &path)
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((expected, rest)) => match path.split_first() {
+            Some((segment, path)) => {
+                segment_matches(expected, segment) && segments_match(rest, path)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Match a single segment, where each `*` matches any run of characters.
+fn segment_matches(pattern: &str, segment: &str) -> bool {
+    if is_pavex_param(pattern) {
+        return pattern == segment;
+    }
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = segment.strip_prefix(first) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last, middle)) = parts.split_last() else {
+        // no wildcard in the pattern
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Whether a segment is a Pavex param, `:name` or `*name`, rather than a glob.
+fn is_pavex_param(segment: &str) -> bool {
+    segment
+        .strip_prefix(':')
+        .or_else(|| segment.strip_prefix('*'))
+        .and_then(|name| name.chars().next())
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal_segments_match_exactly() {
+        assert!(glob_matches("/about", "/about"));
+        assert!(glob_matches("/", "/"));
+        assert!(!glob_matches("/about", "/about/team"));
+        assert!(!glob_matches("/about", "/abou"));
+    }
+
+    #[test]
+    fn star_matches_within_a_segment() {
+        assert!(glob_matches("/products/*", "/products/:id"));
+        assert!(glob_matches("/products/*", "/products/shoes"));
+        assert!(glob_matches("/reports/*-2024", "/reports/sales-2024"));
+        assert!(glob_matches("/*.html", "/index.html"));
+        assert!(!glob_matches("/products/*", "/products/shoes/red"));
+        assert!(!glob_matches("/products/*", "/products"));
+    }
+
+    #[test]
+    fn double_star_spans_segments() {
+        assert!(glob_matches("/admin/**", "/admin"));
+        assert!(glob_matches("/admin/**", "/admin/users/:id"));
+        assert!(glob_matches("/**/edit", "/posts/:id/edit"));
+        assert!(glob_matches("/**", "/"));
+        assert!(!glob_matches("/admin/**", "/administrator"));
+    }
+
+    #[test]
+    fn pavex_params_match_exactly() {
+        assert!(glob_matches("/*path", "/*path"));
+        assert!(!glob_matches("/*path", "/mypath"));
+        assert!(!glob_matches("/*path", "/path"));
+        assert!(glob_matches("/products/:id", "/products/:id"));
+        assert!(!glob_matches("/products/:id", "/products/shoes"));
+        assert!(!glob_matches("/products/:id", "/products/:slug"));
+    }
+
+    #[test]
+    fn exclusions_keep_exact_paths_exact() {
+        let excluded = vec!["/*path".to_string(), "/api/**".to_string()];
+        assert!(is_excluded(&excluded, "/*path"));
+        assert!(is_excluded(&excluded, "/api/users"));
+        assert!(!is_excluded(&excluded, "/mypath"));
+        assert!(!is_excluded(&excluded, "/"));
+    }
+}
@@ -0,0 +1,192 @@
+use crate::trailing_slash::TrailingSlash;
+use crate::{PavexRouteList, PavexRouteListing};
+use pavex::response::Response;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// What `render_route` responds with when it's called for a path that the Leptos router doesn't
+/// know about. This usually means the Pavex blueprint was generated from a different route list
+/// than the one the server was started with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RouteMissPolicy {
+    /// Respond with a `404 Not Found`.
+    #[default]
+    NotFound,
+    /// Respond with a `500 Internal Server Error`, since the blueprint and the Leptos router
+    /// disagreeing is a bug in the application.
+    InternalServerError,
+}
+
+impl RouteMissPolicy {
+    /// Build the Response for a missed route.
+    pub fn response(&self) -> Response {
+        match self {
+            RouteMissPolicy::NotFound => Response::not_found(),
+            RouteMissPolicy::InternalServerError => Response::internal_server_error(),
+        }
+    }
+}
+
+/// An index of the routes generated from the Leptos router, keyed by their Pavex path.
+///
+/// It's meant to be built once, from the prebuilt `Vec<PavexRouteListing>`, by registering
+/// [`PavexRouteTable::new`] as a singleton constructor in your blueprint:
+/// ```rust,ignore
+/// bp.singleton(f!(leptos_pavex::route_table::PavexRouteTable::new));
+/// ```
+/// If you want a different [`RouteMissPolicy`], or statically rendered routes to be regenerated,
+/// register your own constructor instead.
+#[derive(Clone, Debug, Default)]
+pub struct PavexRouteTable {
+    routes: HashMap<String, PavexRouteListing>,
+    miss_policy: RouteMissPolicy,
+    static_revalidation: Option<Duration>,
+}
+
+impl PavexRouteTable {
+    /// Index a list of routes by their path.
+    pub fn new(routes: &PavexRouteList) -> Self {
+        Self {
+            routes: routes
+                .iter()
+                .flat_map(|listing| {
+                    // Routes that render on both paths are found under either of them
+                    let alternate = match listing.trailing_slash() {
+                        TrailingSlash::Both => listing.alternate_path(),
+                        TrailingSlash::Strict | TrailingSlash::Redirect => None,
+                    };
+                    std::iter::once(listing.path().to_string())
+                        .chain(alternate)
+                        .map(|path| (path, listing.clone()))
+                })
+                .collect(),
+            miss_policy: RouteMissPolicy::default(),
+            static_revalidation: None,
+        }
+    }
+
+    /// Set the response used when a route can't be found in the table.
+    pub fn with_miss_policy(mut self, miss_policy: RouteMissPolicy) -> Self {
+        self.miss_policy = miss_policy;
+        self
+    }
+
+    /// Regenerate statically rendered pages once they are older than `revalidate_after`.
+    /// Stale pages are still served while they're regenerated in the background.
+    pub fn with_static_revalidation(mut self, revalidate_after: Duration) -> Self {
+        self.static_revalidation = Some(revalidate_after);
+        self
+    }
+
+    /// Get the listing registered for a Pavex path pattern.
+    pub fn get(&self, path: &str) -> Option<&PavexRouteListing> {
+        self.routes.get(path)
+    }
+
+    /// Whether the Leptos router has a route for a `RequestUrl`, which is matched without the
+    /// prefix the app is mounted under. Only the paths written in the router count: Pavex also
+    /// sends the alternates of a route's trailing slash to `render_route`, and the router renders
+    /// its fallback for them.
+    pub fn router_matches(&self, url: &str) -> bool {
+        let path = url_path(url);
+        self.routes.values().any(|listing| {
+            let route = listing
+                .mount()
+                .strip(listing.path())
+                .unwrap_or_else(|| listing.path().to_string());
+            pattern_matches(&route, path)
+        })
+    }
+
+    /// The response used when a route can't be found in the table.
+    pub fn miss_policy(&self) -> RouteMissPolicy {
+        self.miss_policy
+    }
+
+    /// How old a statically rendered page can get before it's regenerated, if ever.
+    pub fn static_revalidation(&self) -> Option<Duration> {
+        self.static_revalidation
+    }
+
+    /// All the listings in the table.
+    pub fn listings(&self) -> impl Iterator<Item = &PavexRouteListing> {
+        self.routes.values()
+    }
+
+    /// The number of routes in the table.
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Whether the table holds no routes.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+/// The path of a `RequestUrl`, which also has the scheme and host when the request's origin is
+/// known.
+fn url_path(url: &str) -> &str {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |index| &rest[index..]),
+        None => url,
+    };
+    path.split(['?', '#']).next().unwrap_or(path)
+}
+
+/// Match a request path against a Pavex path pattern, where `:name` matches a single segment and
+/// `*name` matches the rest of the path.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let mut segments = path.split('/').filter(|s| !s.is_empty());
+    for expected in pattern.split('/').filter(|s| !s.is_empty()) {
+        if expected.starts_with('*') {
+            return true;
+        }
+        match segments.next() {
+            Some(_) if expected.starts_with(':') => {}
+            Some(segment) if segment == expected => {}
+            _ => return false,
+        }
+    }
+    segments.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mount::MountPoint;
+    use leptos_router::Method;
+
+    fn listing(path: &str) -> PavexRouteListing {
+        PavexRouteListing::new(path.to_string(), Default::default(), [Method::Get], None)
+    }
+
+    #[test]
+    fn router_matches_its_own_routes() {
+        let table = PavexRouteTable::new(&vec![listing("/"), listing("/products/:id")]);
+        assert!(table.router_matches("/"));
+        assert!(table.router_matches("/products/1"));
+        assert!(table.router_matches("/products/1?color=red"));
+        assert!(table.router_matches("https://example.com/products/1"));
+        assert!(!table.router_matches("/products"));
+        assert!(!table.router_matches("/products/1/reviews"));
+        assert!(!table.router_matches("https://example.com/missing"));
+    }
+
+    #[test]
+    fn router_matches_without_the_mount_point() {
+        let table = PavexRouteTable::new(&vec![
+            listing("/products/:id").with_mount(MountPoint::new("/shop"))
+        ]);
+        assert!(table.get("/shop/products/:id").is_some());
+        assert!(table.router_matches("/products/1"));
+        assert!(!table.router_matches("/shop/products/1"));
+    }
+
+    #[test]
+    fn splats_match_the_rest_of_the_path() {
+        let table = PavexRouteTable::new(&vec![listing("/docs/*path")]);
+        assert!(table.router_matches("/docs/guide/routing"));
+        assert!(!table.router_matches("/blog/post"));
+    }
+}
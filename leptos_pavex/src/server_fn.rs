@@ -1,17 +1,21 @@
+use crate::body_limit::ServerFnBodyLimits;
+use crate::cors::{add_cors_headers, CorsPolicy};
+use crate::error_status::ErrorStatuses;
+use crate::origin::RequestOrigin;
 use crate::pavex_helpers::ServerFnOwner;
 use crate::request_parts::RequestParts;
-use crate::response_options::ResponseOptions;
+use crate::response_options::{ResponseOptions, ResponseParts};
 use crate::{request::PavexRequest, response::PavexResponse};
 use dashmap::DashMap;
-use leptos::prelude::{provide_context, ScopedFuture};
+use leptos::prelude::{provide_context, use_context, ScopedFuture};
 use leptos::server_fn::middleware::Service;
 use leptos::server_fn::{codec::Encoding, initialize_server_fn_map, ServerFn, ServerFnTraitObj};
 use once_cell::sync::Lazy;
-use pavex::http::{HeaderName, Method as HttpMethod, StatusCode};
-use pavex::request::body::RawIncomingBody;
+use pavex::http::uri::PathAndQuery;
+use pavex::http::{Method as HttpMethod, StatusCode};
+use pavex::request::body::{BodySizeLimit, RawIncomingBody};
 use pavex::request::RequestHead;
 use pavex::response::Response;
-use url::Url;
 
 #[allow(unused)] // used by server integrations
 type LazyServerFnMap<Req, Res> = Lazy<DashMap<(String, HttpMethod), ServerFnTraitObj<Req, Res>>>;
@@ -47,58 +51,64 @@ pub async fn handle_server_fns(
     req_head: RequestHead,
     req_body: RawIncomingBody,
     context: ServerFnOwner,
+    cors: &CorsPolicy,
+    body_size_limit: &BodySizeLimit,
+    body_limits: &ServerFnBodyLimits,
+    error_statuses: &ErrorStatuses,
 ) -> Response {
-    handle_server_fns_with_context(req_head, req_body, context).await
+    // Requests from other origins can only read the response if it allows them to
+    let cors_headers = cors.response_headers(&req_head);
+    let mut res = handle_server_fns_with_context(
+        req_head,
+        req_body,
+        context,
+        *body_size_limit,
+        body_limits,
+        error_statuses,
+    )
+    .await;
+    add_cors_headers(res.headers_mut(), cors_headers);
+    res
 }
 pub async fn handle_server_fns_with_context(
     req_head: RequestHead,
     req_body: RawIncomingBody,
     context: ServerFnOwner,
+    body_size_limit: BodySizeLimit,
+    body_limits: &ServerFnBodyLimits,
+    error_statuses: &ErrorStatuses,
 ) -> Response {
     let pq = req_head.target.path_and_query().unwrap();
-    match crate::server_fn::get_server_fn_by_path(pq.as_str(), &req_head.method) {
+    // Server functions are registered under the prefix the app is mounted at, but looked up by
+    // their own path
+    let path = context
+        .owner()
+        .with(use_context::<RequestOrigin>)
+        .and_then(|origin| origin.mount().strip(pq.as_str()))
+        .unwrap_or_else(|| pq.to_string());
+    match crate::server_fn::get_server_fn_by_path(&path, &req_head.method) {
         Some(lepfn) => {
+            let body_size_limit = body_limits.limit_for(lepfn.path(), body_size_limit);
             let owner = context.owner();
+            // The status of a failed call is looked up in the Owner the response is built in
+            let error_statuses = error_statuses.clone();
             let blah = owner.with(|| {
                 ScopedFuture::new(async move {
-                    let req_parts = RequestParts::new_from_req(&req_head);
-                    provide_context(req_parts.clone());
+                    // The RequestParts provided by pass_leptos_context carry the real origin
+                    let req_parts = use_context::<RequestParts>()
+                        .unwrap_or_else(|| RequestParts::new_from_req(&req_head));
+                    provide_context(req_parts);
+                    // Only the headers set by the server function are added to its response
                     let res_options = ResponseOptions::default();
+                    res_options.overwrite(ResponseParts::default_without_headers());
                     provide_context(res_options.clone());
-                    let pavex_req = PavexRequest::new_from_req(req_head, req_body);
-                    let (mut pavex_res, req_parts, res_options) =
-                        (lepfn.clone().run(pavex_req).await, req_parts, res_options);
-                    // If the Accept header contains text/html, then this is a request from
-                    // a regular html form, so we should set up a redirect to either the referrer
-                    // or the user specified location
-
-                    let req_headers = req_parts.headers();
-                    let accepts = req_headers.get("Accept");
-                    let accepts_html_bool = match accepts {
-                        Some(h) => match h.to_str().unwrap().contains("text/html") {
-                            true => true,
-                            false => false,
-                        },
-                        None => false,
-                    };
-
-                    if accepts_html_bool {
-                        let referrer = &req_headers.get("Referer");
-                        let location = &req_headers.get("Location");
-                        if location.is_none() {
-                            if let Some(referrer) = *referrer {
-                                res_options.insert_header(
-                                    HeaderName::from_static("location"),
-                                    referrer.to_owned(),
-                                );
-                            }
-                        }
-                        // Set status
-                        if res_options.status().is_none() {
-                            res_options.set_status(StatusCode::FOUND);
-                        }
-                    }
-
+                    provide_context(error_statuses);
+                    let pavex_req = PavexRequest::new_from_req(req_head, req_body)
+                        .with_body_size_limit(body_size_limit);
+                    // Requests from plain HTML forms are redirected by server_fn itself, through
+                    // PavexResponse::redirect, and calls to redirect() are recorded in the
+                    // ResponseOptions, which are applied on top
+                    let mut pavex_res = lepfn.clone().run(pavex_req).await;
                     pavex_res
                         .0
                         .headers_mut()
@@ -131,15 +141,12 @@ pub fn get_server_fn_by_path(
     path: &str,
     method: &HttpMethod,
 ) -> Option<ServerFnTraitObj<PavexRequest, PavexResponse>> {
-    // Sanitize Url to prevent query string or ids causing issues. To do that Url wants a full url,
-    // so we give it a fake one. We're only using the path anyway!
-    let full_url = format!("http://leptos.dev{}", path);
-
-    let Ok(url) = Url::parse(&full_url) else {
-        println!("Failed to parse: {full_url:?}");
+    // Strip the query string, since only the path identifies the server function
+    let Ok(path) = path.parse::<PathAndQuery>() else {
+        tracing::warn!("Failed to parse the server function path {path:?}");
         return None;
     };
     REGISTERED_SERVER_FUNCTIONS
-        .get_mut(&(url.path().to_string(), method.clone()))
+        .get_mut(&(path.path().to_string(), method.clone()))
         .map(|f| f.clone())
 }
@@ -0,0 +1,504 @@
+use crate::mount::MountPoint;
+use crate::origin::RequestOrigin;
+use crate::pavex_helpers::{generate_app_function, stub_request_head};
+use crate::render_options::RenderOptions;
+use crate::{
+    async_stream_builder, handle_response, pass_leptos_context_with_origin, PavexRouteList,
+    PavexRouteListing, RouteType,
+};
+use any_spawner::Executor;
+use bytes::Bytes;
+use dashmap::{DashMap, DashSet};
+use futures::future::{BoxFuture, Shared};
+use futures::FutureExt;
+use http_body_util::BodyExt;
+use leptos::config::LeptosOptions;
+use leptos::IntoView;
+use leptos_router::StaticMode;
+use once_cell::sync::{Lazy, OnceCell};
+use pavex::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use pavex::http::{HeaderValue, StatusCode};
+use pavex::response::body::raw::Full;
+use pavex::response::Response;
+use reactive_graph::computed::ScopedFuture;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+/// Paths whose static page has been invalidated, and has to be rendered again on its next request
+static INVALIDATED: Lazy<DashSet<String>> = Lazy::new(DashSet::new);
+
+/// Paths whose static page is being regenerated in the background
+static REGENERATING: Lazy<DashSet<String>> = Lazy::new(DashSet::new);
+
+/// A page being rendered to be written to the site root, awaited by every request for it
+type SharedRender = Shared<BoxFuture<'static, Option<String>>>;
+
+/// Paths whose static page is being rendered, so that concurrent requests for them render it once
+static RENDERING: Lazy<DashMap<String, SharedRender>> = Lazy::new(DashMap::new);
+
+/// The static paths of the static routes, resolved by `build_static_routes`
+static STATIC_PATHS: OnceCell<HashSet<String>> = OnceCell::new();
+
+/// Renders the app for a path with a stub request, kept by `build_static_routes` to render the
+/// static pages generated at runtime
+type PageRenderer =
+    Box<dyn Fn(String, MountPoint) -> BoxFuture<'static, Option<String>> + Send + Sync>;
+
+static PAGE_RENDERER: OnceCell<PageRenderer> = OnceCell::new();
+
+/// The path of the file a statically rendered route is written to, inside the site root.
+/// Returns `None` if the path would escape the site root.
+pub fn static_file_path(options: &LeptosOptions, path: &str) -> Option<PathBuf> {
+    let relative = Path::new(path.trim_matches('/'));
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+    Some(
+        Path::new(&*options.site_root)
+            .join(relative)
+            .join("index.html"),
+    )
+}
+
+/// The values the params of static routes are rendered for, by route path and param name.
+///
+/// leptos_router doesn't carry the static data of its routes yet, so it's given to
+/// `build_static_routes` instead:
+/// ```rust,ignore
+/// let params = StaticParams::new().with_param("/products/:id", "id", ["shoes", "hats"]);
+/// build_static_routes(&leptos_options, app_fn, additional_context, &routes, &params).await?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StaticParams(HashMap<String, HashMap<String, Vec<String>>>);
+
+impl StaticParams {
+    /// No params, so that only static routes without any are rendered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render the static route at a Pavex path of the app, like `/products/:id`, for each of these
+    /// values of one of its params. The path is the one in the app, without its mount point.
+    pub fn with_param(
+        mut self,
+        route: impl Into<String>,
+        param: impl Into<String>,
+        values: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.0
+            .entry(route.into())
+            .or_default()
+            .entry(param.into())
+            .or_default()
+            .extend(values.into_iter().map(Into::into));
+        self
+    }
+
+    fn values(&self, route: &str, param: &str) -> &[String] {
+        self.0
+            .get(route)
+            .and_then(|params| params.get(param))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Resolve every concrete path a static route should be rendered for, by filling its params with
+/// their static values. Routes with a param without values have no paths.
+pub fn static_paths(listing: &PavexRouteListing, params: &StaticParams) -> Vec<String> {
+    let route = listing
+        .mount()
+        .strip(listing.path())
+        .unwrap_or_else(|| listing.path().to_string());
+    let mut paths = vec![String::new()];
+    for segment in listing.path().split('/').filter(|s| !s.is_empty()) {
+        let values = match segment
+            .strip_prefix(':')
+            .or_else(|| segment.strip_prefix('*'))
+        {
+            Some(name) => params.values(&route, name).to_vec(),
+            None => vec![segment.to_string()],
+        };
+        paths = paths
+            .iter()
+            .flat_map(|path| values.iter().map(move |value| format!("{path}/{value}")))
+            .collect();
+    }
+    paths
+        .into_iter()
+        .map(|path| {
+            if path.is_empty() {
+                "/".to_string()
+            } else {
+                path
+            }
+        })
+        .collect()
+}
+
+/// Render every route marked `StaticMode::Upfront` to an HTML file in the site root, for each of
+/// its static paths. Routes marked `StaticMode::Incremental` are left to be rendered on the first
+/// request for one of their static paths.
+///
+/// The static paths and the app are kept to render the pages generated at runtime, so that they're
+/// rendered with a stub request rather than the visitor's, and only for those paths. Without them,
+/// static routes are rendered for each request like other routes and never written to the site
+/// root.
+///
+/// This should be run once at startup, before the server starts accepting requests.
+#[tracing::instrument(level = "trace", fields(error), skip_all)]
+pub async fn build_static_routes<IV>(
+    options: &LeptosOptions,
+    app_fn: impl Fn() -> IV + Clone + Send + Sync + 'static,
+    additional_context: impl Fn() + Clone + Send + Sync + 'static,
+    routes: &PavexRouteList,
+    params: &StaticParams,
+) -> Result<(), io::Error>
+where
+    IV: IntoView + 'static,
+{
+    keep_page_renderer(app_fn.clone(), additional_context.clone());
+    let mut persisted = HashSet::new();
+    for listing in routes.iter().filter(|l| l.static_mode().is_some()) {
+        for path in static_paths(listing, params) {
+            persisted.insert(page_key(&path));
+            if listing.static_mode() != Some(StaticMode::Upfront) {
+                continue;
+            }
+            let page = render_mounted_page(
+                &path,
+                listing.mount(),
+                app_fn.clone(),
+                additional_context.clone(),
+            );
+            match page.await {
+                Some(html) => write_static_file(options, &path, &html)?,
+                None => tracing::warn!("Static route {path} did not render, skipping it"),
+            }
+        }
+    }
+    let _ = STATIC_PATHS.set(persisted);
+    Ok(())
+}
+
+/// Keep the app and its context to render static pages at runtime. Only the first ones are kept.
+fn keep_page_renderer<IV>(
+    app_fn: impl Fn() -> IV + Clone + Send + Sync + 'static,
+    additional_context: impl Fn() + Clone + Send + Sync + 'static,
+) where
+    IV: IntoView + 'static,
+{
+    let _ = PAGE_RENDERER.set(Box::new(move |path, mount| {
+        let app_fn = app_fn.clone();
+        let additional_context = additional_context.clone();
+        async move { render_mounted_page(&path, &mount, app_fn, additional_context).await }.boxed()
+    }));
+}
+
+/// Render the app for a path, returning its HTML if it rendered successfully.
+pub async fn render_static_page<IV>(
+    path: &str,
+    app_fn: impl Fn() -> IV + Clone + Send + 'static,
+    additional_context: impl Fn() + Clone + Send + 'static,
+) -> Option<String>
+where
+    IV: IntoView + 'static,
+{
+    render_mounted_page(path, &MountPoint::root(), app_fn, additional_context).await
+}
+
+/// Render the app for a path on the server, for an app mounted under a path prefix.
+async fn render_mounted_page<IV>(
+    path: &str,
+    mount: &MountPoint,
+    app_fn: impl Fn() -> IV + Clone + Send + 'static,
+    additional_context: impl Fn() + Clone + Send + 'static,
+) -> Option<String>
+where
+    IV: IntoView + 'static,
+{
+    let req_head = stub_request_head(path);
+    let origin = RequestOrigin::untrusted(&req_head).with_mount(mount.clone());
+    let (owner, meta_output) = pass_leptos_context_with_origin(
+        &RouteType::Component,
+        &req_head,
+        &origin,
+        additional_context,
+    );
+    let app = owner.with(|| generate_app_function(app_fn));
+    let render_options = RenderOptions::default();
+    let res = owner
+        .with(|| {
+            ScopedFuture::new(handle_response(
+                req_head,
+                app,
+                meta_output,
+                &render_options,
+                async_stream_builder,
+            ))
+        })
+        .await;
+    if res.status() != StatusCode::OK {
+        return None;
+    }
+    let html = res.into_parts().1.collect().await.ok()?.to_bytes();
+    String::from_utf8(html.to_vec()).ok()
+}
+
+/// Whether the page for a path of a static route is written to the site root. Only the static
+/// paths resolved by `build_static_routes` are, so that requests for other param values can't
+/// fill the site root.
+pub(crate) fn persists_path(path: &str) -> bool {
+    STATIC_PATHS
+        .get()
+        .is_some_and(|paths| paths.contains(&page_key(path)))
+}
+
+/// Render the page for a path of a static route with a stub request, like `build_static_routes`
+/// does, and write it to the site root. Concurrent requests for the path wait for the same render.
+/// Returns `None` if the app wasn't kept by `build_static_routes`, or if the page didn't render.
+pub(crate) async fn render_persisted_page(
+    options: &LeptosOptions,
+    path: &str,
+    mount: &MountPoint,
+) -> Option<Response> {
+    let html = render_once(options, path, mount)?.await?;
+    Some(html_response(html.into()))
+}
+
+/// The render of the page for a path, shared with the renders already in flight for it.
+fn render_once(options: &LeptosOptions, path: &str, mount: &MountPoint) -> Option<SharedRender> {
+    let render = PAGE_RENDERER.get()?;
+    let key = page_key(path);
+    let page = RENDERING.entry(key.clone()).or_insert_with(|| {
+        let options = options.clone();
+        let path = path.to_string();
+        let page = render(path.clone(), mount.clone());
+        async move {
+            // Dropped once the page is written, or if rendering it panics
+            let _rendering = Rendering(key);
+            let html = page.await?;
+            if let Err(e) = write_static_file(&options, &path, &html) {
+                tracing::error!("Failed to write static route {path}: {e}");
+            }
+            Some(html)
+        }
+        .boxed()
+        .shared()
+    });
+    Some(page.clone())
+}
+
+/// Marks the page for a path as being rendered for as long as it's alive.
+struct Rendering(String);
+
+impl Drop for Rendering {
+    fn drop(&mut self) {
+        RENDERING.remove(&self.0);
+    }
+}
+
+/// The state of a statically rendered page in the site root.
+pub enum StaticPage {
+    /// The page was generated within the revalidation interval, and can be served as is.
+    Fresh(Response),
+    /// The page is older than the revalidation interval. It can still be served, but should be
+    /// regenerated.
+    Stale(Response),
+    /// The page hasn't been generated yet, or has been invalidated, and has to be rendered.
+    Missing,
+}
+
+/// Look up the statically rendered page for a path in the site root. If `revalidate_after` is set,
+/// pages older than it are reported as stale.
+pub fn static_page(
+    options: &LeptosOptions,
+    path: &str,
+    revalidate_after: Option<Duration>,
+) -> StaticPage {
+    if INVALIDATED.contains(&page_key(path)) {
+        return StaticPage::Missing;
+    }
+    let Some(file) = static_file_path(options, path) else {
+        return StaticPage::Missing;
+    };
+    let Ok(html) = fs::read(&file) else {
+        return StaticPage::Missing;
+    };
+
+    let res = html_response(html.into());
+    let stale = revalidate_after.is_some_and(|revalidate_after| {
+        fs::metadata(&file)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_none_or(|age| age >= revalidate_after)
+    });
+    if stale {
+        StaticPage::Stale(res)
+    } else {
+        StaticPage::Fresh(res)
+    }
+}
+
+/// Invalidate the statically rendered page for a path, so that it's rendered again on its next
+/// request instead of being served from the site root. This can be called from a server function
+/// once the data the page is built from has changed.
+///
+/// Invalidations are only known to the process they're made in. Other instances of the server keep
+/// serving their copy of the page until it's stale, or until it's rewritten in a site root they
+/// share with this one.
+/// ```rust,ignore
+/// #[server]
+/// pub async fn update_product(id: String, product: Product) -> Result<(), ServerFnError> {
+///     save_product(&id, product).await?;
+///     leptos_pavex::static_routes::invalidate_static_route(&format!("/products/{id}"));
+///     Ok(())
+/// }
+/// ```
+pub fn invalidate_static_route(path: &str) {
+    INVALIDATED.insert(page_key(path));
+}
+
+/// Regenerate the stale page for a path in the background, with a stub request. Nothing is done
+/// if the page is already being regenerated.
+pub(crate) fn regenerate_page(options: &LeptosOptions, path: &str, mount: &MountPoint) {
+    let Some(regenerating) = Regenerating::start(path) else {
+        return;
+    };
+    let options = options.clone();
+    let path = path.to_string();
+    let mount = mount.clone();
+    Executor::spawn(async move {
+        // Dropped once the page is written, or if rendering it panics
+        let _regenerating = regenerating;
+        let page = render_persisted_page(&options, &path, &mount).await;
+        if page.is_none() {
+            tracing::warn!("Static route {path} did not render, keeping the stale page");
+        }
+    });
+}
+
+/// Marks the page for a path as being regenerated for as long as it's alive.
+struct Regenerating(String);
+
+impl Regenerating {
+    /// Returns `None` if the page is already being regenerated.
+    fn start(path: &str) -> Option<Self> {
+        let key = page_key(path);
+        REGENERATING.insert(key.clone()).then_some(Self(key))
+    }
+}
+
+impl Drop for Regenerating {
+    fn drop(&mut self) {
+        REGENERATING.remove(&self.0);
+    }
+}
+
+fn page_key(path: &str) -> String {
+    match path.trim_end_matches('/') {
+        "" => "/".to_string(),
+        path => path.to_string(),
+    }
+}
+
+/// Write the HTML for a static route to its file in the site root.
+pub fn write_static_file(options: &LeptosOptions, path: &str, html: &str) -> Result<(), io::Error> {
+    let file = static_file_path(options, path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{path} is not a valid static route path"),
+        )
+    })?;
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(file, html)?;
+    INVALIDATED.remove(&page_key(path));
+    Ok(())
+}
+
+fn html_response(html: Bytes) -> Response {
+    Response::ok()
+        .append_header(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/html; charset=utf-8"),
+        )
+        .append_header(CONTENT_LENGTH, html.len().into())
+        .set_raw_body(Full::new(html))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::future::join;
+    use leptos_router::SsrMode;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn listing(path: &str) -> PavexRouteListing {
+        PavexRouteListing::new(
+            path.to_string(),
+            SsrMode::Async,
+            [leptos_router::Method::Get],
+            None,
+        )
+    }
+
+    #[test]
+    fn params_are_filled_with_their_static_values() {
+        let params = StaticParams::new()
+            .with_param("/shop/:category/:id", "category", ["hats"])
+            .with_param("/shop/:category/:id", "id", ["1", "2"]);
+        assert_eq!(
+            static_paths(&listing("/shop/:category/:id"), &params),
+            ["/shop/hats/1", "/shop/hats/2"]
+        );
+    }
+
+    #[test]
+    fn params_without_values_have_no_paths() {
+        let params = StaticParams::new().with_param("/products/:id", "slug", ["hats"]);
+        assert!(static_paths(&listing("/products/:id"), &params).is_empty());
+        assert_eq!(static_paths(&listing("/"), &params), ["/"]);
+    }
+
+    #[test]
+    fn mounted_routes_take_the_params_of_their_app_path() {
+        let listing = listing("/products/:id").with_mount(MountPoint::new("/app"));
+        let params = StaticParams::new().with_param("/products/:id", "id", ["1"]);
+        assert_eq!(static_paths(&listing, &params), ["/app/products/1"]);
+    }
+
+    #[test]
+    fn concurrent_renders_of_a_path_are_shared() {
+        static RENDERS: AtomicUsize = AtomicUsize::new(0);
+        let _ = PAGE_RENDERER.set(Box::new(|path, _| {
+            RENDERS.fetch_add(1, Ordering::SeqCst);
+            async move { Some(format!("<p>{path}</p>")) }.boxed()
+        }));
+        let site_root = std::env::temp_dir().join("leptos_pavex_concurrent_renders");
+        let options = LeptosOptions::builder()
+            .output_name("test")
+            .site_root(site_root.to_str().unwrap())
+            .build();
+        let render = || render_once(&options, "/shared", &MountPoint::root()).unwrap();
+
+        let (first, second) = block_on(join(render(), render()));
+        assert_eq!(first.as_deref(), Some("<p>/shared</p>"));
+        assert_eq!(first, second);
+        assert_eq!(RENDERS.load(Ordering::SeqCst), 1);
+        assert!(site_root.join("shared/index.html").exists());
+
+        // Once the page is written, rendering it again starts a new render
+        block_on(render());
+        assert_eq!(RENDERS.load(Ordering::SeqCst), 2);
+        let _ = fs::remove_dir_all(site_root);
+    }
+}
@@ -41,7 +41,7 @@ where
     ) -> std::task::Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
         let stream: Pin<&mut S> = self.to_inner_pin();
 
-        S::poll_next(stream, cx).map(|o| o.map(|r| r.map_err(|e| e.into()).map(Frame::data)))
+        S::poll_next(stream, cx).map(|o| o.map(|r| r.map(Frame::data)))
     }
 }
 
@@ -0,0 +1,64 @@
+use crate::PavexRouteList;
+use pavex::http::header::LOCATION;
+use pavex::http::{HeaderValue, StatusCode};
+use pavex::request::RequestHead;
+use pavex::response::Response;
+
+/// How a route answers the path that only differs from its own by a trailing slash, like
+/// `/about/` for `/about`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrailingSlash {
+    /// Only the path as it's written in the Leptos router is registered. The other one is left to
+    /// the rest of the Pavex router, usually ending in a `404 Not Found`.
+    #[default]
+    Strict,
+    /// The other path is registered too, and redirects to the one written in the Leptos router with
+    /// a `308 Permanent Redirect`.
+    Redirect,
+    /// Both paths are registered, and render the route.
+    Both,
+}
+
+/// Set the trailing slash policy of every route in a list, before it's registered with
+/// `add_leptos_routes`:
+/// ```rust,ignore
+/// let routes = set_trailing_slash(generate_route_list(app), TrailingSlash::Redirect);
+/// add_leptos_routes(&routes, bp);
+/// ```
+pub fn set_trailing_slash(routes: PavexRouteList, policy: TrailingSlash) -> PavexRouteList {
+    routes
+        .into_iter()
+        .map(|listing| listing.with_trailing_slash(policy))
+        .collect()
+}
+
+/// The path that only differs from `path` by a trailing slash. The root path and paths ending in a
+/// catch-all segment don't have one.
+pub fn alternate_path(path: &str) -> Option<String> {
+    if path == "/" || path.rsplit('/').find(|s| !s.is_empty())?.starts_with('*') {
+        return None;
+    }
+    Some(match path.strip_suffix('/') {
+        Some(path) => path.to_string(),
+        None => format!("{path}/"),
+    })
+}
+
+/// Redirect a request to the path the Leptos router uses for the route, adding or removing the
+/// trailing slash and keeping the query. The `308 Permanent Redirect` keeps the method and body
+/// of the request.
+pub fn redirect_to_canonical(req_head: &RequestHead) -> Response {
+    let Some(path) = alternate_path(req_head.target.path()) else {
+        return Response::not_found();
+    };
+    let location = match req_head.target.query() {
+        Some(query) => format!("{path}?{query}"),
+        None => path,
+    };
+    match HeaderValue::from_str(&location) {
+        Ok(location) => {
+            Response::new(StatusCode::PERMANENT_REDIRECT).insert_header(LOCATION, location)
+        }
+        Err(_) => Response::bad_request(),
+    }
+}